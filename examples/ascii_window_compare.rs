@@ -0,0 +1,54 @@
+//! Times the window comparison used by `sss::ascii::contains` against a
+//! word-at-a-time comparison of the same windows. Run with
+//! `cargo run --release --example ascii_window_compare`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: usize = 2_000;
+
+/// Compares two equal-length byte slices a `usize`-sized chunk at a time.
+fn word_eq(window: &[u8], pattern: &[u8]) -> bool {
+    const WORD: usize = std::mem::size_of::<usize>();
+    let word = |chunk: &[u8]| usize::from_ne_bytes(chunk.try_into().unwrap());
+
+    let window_chunks = window.chunks_exact(WORD);
+    let pattern_chunks = pattern.chunks_exact(WORD);
+    let (window_rest, pattern_rest) = (window_chunks.remainder(), pattern_chunks.remainder());
+
+    window_chunks
+        .zip(pattern_chunks)
+        .all(|(a, b)| word(a) == word(b))
+        && window_rest == pattern_rest
+}
+
+/// Compares the pattern against every window of the text, so that each
+/// comparison runs to the last byte before failing.
+fn time(text: &[u8], pattern: &[u8], eq: impl Fn(&[u8], &[u8]) -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let found = text
+            .windows(pattern.len())
+            .filter(|&window| eq(black_box(window), black_box(pattern)))
+            .count();
+        black_box(found);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    for m in [4, 8, 16, 32, 64, 256] {
+        // every window but the last differs only in its final byte
+        let mut pattern = vec![b'a'; m];
+        pattern[m - 1] = b'b';
+        let mut text = vec![b'a'; 4_096];
+        text.extend_from_slice(&pattern);
+
+        let slice = time(&text, &pattern, |a, b| a == b);
+        let word = time(&text, &pattern, word_eq);
+        println!(
+            "{m:>4} bytes: slice == {:>8.2?}, word at a time {:>8.2?}",
+            slice, word
+        );
+    }
+}
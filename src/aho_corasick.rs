@@ -0,0 +1,325 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Aho-Corasick searches for many patterns at once in a single pass over the
+/// text. The automaton is built once and can be reused to search many texts.
+///
+/// The automaton is a prefix tree of the patterns (the goto function) in
+/// which every node also has a failure link to the node for the longest
+/// proper suffix of its path that is also in the tree. On a mismatch the
+/// search follows failure links instead of moving back in the text, so each
+/// text char is read once. Each node lists the patterns that end there (its
+/// output), including those reached through its failure links.
+pub struct AhoCorasick {
+    next: Vec<HashMap<char, usize>>,
+    fail: Vec<usize>,
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+    max_len: usize,
+}
+
+impl AhoCorasick {
+    /// Builds the automaton for the patterns, which are identified by their
+    /// index in `patterns` in every match. The prefix tree is built first,
+    /// and the failure links are then set breadth first from the root.
+    pub fn new(patterns: &[&str]) -> Self {
+        let mut next = vec![HashMap::new()];
+        let mut outputs = vec![Vec::new()];
+        let mut lengths = Vec::with_capacity(patterns.len());
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut node = 0;
+            for ch in pattern.chars() {
+                node = match next[node].get(&ch) {
+                    Some(&child) => child,
+                    None => {
+                        next.push(HashMap::new());
+                        outputs.push(Vec::new());
+                        let child = next.len() - 1;
+                        next[node].insert(ch, child);
+                        child
+                    }
+                };
+            }
+            outputs[node].push(index);
+            lengths.push(pattern.chars().count());
+        }
+
+        // nodes are visited in order of depth, so the failure link of a node's
+        // parent is always known before the node's own
+        let mut fail = vec![0; next.len()];
+        let mut queue: VecDeque<usize> = next[0].values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(char, usize)> = next[node].iter().map(|(&ch, &c)| (ch, c)).collect();
+            for (ch, child) in children {
+                let mut link = fail[node];
+                while link != 0 && !next[link].contains_key(&ch) {
+                    link = fail[link];
+                }
+                fail[child] = next[link].get(&ch).copied().unwrap_or(0);
+
+                let inherited = outputs[fail[child]].clone();
+                outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        let max_len = lengths.iter().copied().max().unwrap_or(0);
+        Self {
+            next,
+            fail,
+            outputs,
+            lengths,
+            max_len,
+        }
+    }
+
+    /// Returns whether any pattern occurs in the text. Unlike
+    /// [`AhoCorasick::find_first`], this stops at the first char where some
+    /// pattern ends, since it does not matter which one starts leftmost.
+    pub fn is_match(&self, text: &str) -> bool {
+        let mut node = 0;
+        if !self.outputs[node].is_empty() {
+            return true;
+        }
+
+        for ch in text.chars() {
+            node = self.step(node, ch);
+            if !self.outputs[node].is_empty() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the index of the pattern that matches first and the char index
+    /// where it starts, as `(pattern, start)`. The match with the leftmost
+    /// start wins, even if another ends sooner; ties go to the pattern listed
+    /// first. An empty pattern matches at 0.
+    ///
+    /// Matches are discovered in order of where they end, so the scan goes on
+    /// past the first match until no pattern could start any earlier.
+    pub fn find_first(&self, text: &str) -> Option<(usize, usize)> {
+        let mut best = None;
+        self.update_best(0, 0, &mut best);
+
+        let mut node = 0;
+        for (i, ch) in text.chars().enumerate() {
+            if let Some((_, start)) = best {
+                if i >= start + self.max_len {
+                    break;
+                }
+            }
+
+            node = self.step(node, ch);
+            self.update_best(node, i + 1, &mut best);
+        }
+
+        best
+    }
+
+    /// Returns every match, including matches that overlap, as
+    /// `(pattern, start, weight)` where `weight` is `weights[pattern]`. The
+    /// matches are sorted by weight, heaviest first, then by the char index
+    /// where they start, then by pattern. Panics if there are fewer weights
+    /// than patterns.
+    pub fn find_all_ranked(&self, text: &str, weights: &[f64]) -> Vec<(usize, usize, f64)> {
+        let mut found = Vec::new();
+        let mut record = |node: usize, end: usize| {
+            for &index in &self.outputs[node] {
+                found.push((index, end - self.lengths[index], weights[index]));
+            }
+        };
+
+        let mut node = 0;
+        record(node, 0);
+        for (i, ch) in text.chars().enumerate() {
+            node = self.step(node, ch);
+            record(node, i + 1);
+        }
+
+        found.sort_by(|(a, a_start, a_weight), (b, b_start, b_weight)| {
+            b_weight
+                .total_cmp(a_weight)
+                .then(a_start.cmp(b_start))
+                .then(a.cmp(b))
+        });
+        found
+    }
+
+    /// Returns how many times each pattern occurs in the text, by index,
+    /// counting matches that overlap. The text is scanned once, and every
+    /// pattern that ends at a char is read off the outputs of its node.
+    pub fn count_per_pattern(&self, text: &str) -> Vec<usize> {
+        let mut counts = vec![0; self.lengths.len()];
+        let mut tally = |node: usize| {
+            for &index in &self.outputs[node] {
+                counts[index] += 1;
+            }
+        };
+
+        let mut node = 0;
+        tally(node);
+        for ch in text.chars() {
+            node = self.step(node, ch);
+            tally(node);
+        }
+        counts
+    }
+
+    /// Returns every match that differs from its pattern in at most `k`
+    /// chars, as `(pattern, start)`, sorted by the char index where the match
+    /// starts and then by pattern. Only substitutions are allowed, so a match
+    /// is always as long as its pattern. With `k = 0` this finds the same
+    /// matches as [`AhoCorasick::count_per_pattern`] counts.
+    ///
+    /// Failure links cannot be followed here, since after a substitution the
+    /// longest suffix in the tree no longer says which patterns could still
+    /// match. Instead the prefix tree is walked from every start, branching
+    /// into each child while the substitutions used stay within `k`. Each
+    /// walk visits at most every node once, so the worst case is O(nm) for a
+    /// text of n chars and patterns of m chars in total. Small `k` prunes most
+    /// branches early, but the number of nodes reached grows quickly with `k`.
+    pub fn find_all_approx(&self, text: &str, k: usize) -> Vec<(usize, usize)> {
+        let text: Vec<char> = text.chars().collect();
+
+        let mut found = Vec::new();
+        for start in 0..=text.len() {
+            // (node, depth, substitutions so far)
+            let mut stack = vec![(0, 0, 0)];
+            while let Some((node, depth, substitutions)) = stack.pop() {
+                // outputs inherited through failure links end at shallower
+                // depths and belong to other starts
+                for &index in &self.outputs[node] {
+                    if self.lengths[index] == depth {
+                        found.push((index, start));
+                    }
+                }
+
+                let Some(&ch) = text.get(start + depth) else {
+                    continue;
+                };
+                for (&label, &child) in &self.next[node] {
+                    let cost = substitutions + usize::from(label != ch);
+                    if cost <= k {
+                        stack.push((child, depth + 1, cost));
+                    }
+                }
+            }
+        }
+
+        found.sort_unstable_by_key(|&(index, start)| (start, index));
+        found
+    }
+
+    /// Follows the transition for `ch` from `node`, falling back along
+    /// failure links until one exists.
+    fn step(&self, mut node: usize, ch: char) -> usize {
+        while node != 0 && !self.next[node].contains_key(&ch) {
+            node = self.fail[node];
+        }
+        self.next[node].get(&ch).copied().unwrap_or(0)
+    }
+
+    fn update_best(&self, node: usize, end: usize, best: &mut Option<(usize, usize)>) {
+        for &index in &self.outputs[node] {
+            let start = end - self.lengths[index];
+            if best.is_none_or(|(i, s)| (start, index) < (s, i)) {
+                *best = Some((index, start));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AhoCorasick;
+
+    #[test]
+    fn overlapping_patterns() {
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+
+        // "she", "he" and "hers" all match; "she" starts first
+        assert_eq!(automaton.find_first("ushers"), Some((1, 1)));
+        assert_eq!(automaton.find_first("hers"), Some((0, 0)));
+        assert_eq!(automaton.find_first("this"), Some((2, 1)));
+        assert_eq!(automaton.find_first("shiny"), None);
+
+        assert!(automaton.is_match("ushers"));
+        assert!(automaton.is_match("this"));
+        assert!(!automaton.is_match("shiny"));
+        assert!(AhoCorasick::new(&["x", ""]).is_match(""));
+        assert!(!AhoCorasick::new(&[]).is_match("abc"));
+    }
+
+    #[test]
+    fn failure_links_and_outputs() {
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+
+        // after "sh", a mismatch falls back to the node for "h"
+        let node = |path: &str| path.chars().fold(0, |node, ch| automaton.next[node][&ch]);
+        assert_eq!(automaton.fail[node("sh")], node("h"));
+        assert_eq!(automaton.fail[node("she")], node("he"));
+        assert_eq!(automaton.fail[node("hers")], node("s"));
+        assert_eq!(automaton.fail[node("hi")], 0);
+
+        // "she" also outputs "he" through its failure link
+        assert_eq!(automaton.outputs[node("she")], vec![1, 0]);
+    }
+
+    #[test]
+    fn find_all_ranked_by_weight_then_position() {
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        let weights = [1.0, 0.5, 2.0, 3.0];
+
+        assert_eq!(
+            automaton.find_all_ranked("ushers his shed", &weights),
+            vec![
+                (3, 2, 3.0),
+                (2, 7, 2.0),
+                (0, 2, 1.0),
+                (0, 12, 1.0),
+                (1, 1, 0.5),
+                (1, 11, 0.5),
+            ]
+        );
+        assert!(automaton.find_all_ranked("shiny", &weights).is_empty());
+    }
+
+    #[test]
+    fn count_per_pattern_tallies_overlaps() {
+        let automaton = AhoCorasick::new(&["a", "aa", "aaa", "b"]);
+        assert_eq!(automaton.count_per_pattern("aaaa"), vec![4, 3, 2, 0]);
+        assert_eq!(automaton.count_per_pattern("abaab"), vec![3, 1, 0, 2]);
+
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        assert_eq!(
+            automaton.count_per_pattern("ushers said she hers"),
+            vec![3, 2, 0, 2]
+        );
+        assert_eq!(AhoCorasick::new(&[""]).count_per_pattern("ab"), vec![3]);
+    }
+
+    #[test]
+    fn find_all_approx_allows_substitutions() {
+        let automaton = AhoCorasick::new(&["error", "warning", "timeout"]);
+
+        // "warnimg" is one char off "warning"
+        let text = "disk warnimg after timeout";
+        assert_eq!(automaton.find_all_approx(text, 1), vec![(1, 5), (2, 19)]);
+        assert_eq!(automaton.find_all_approx(text, 0), vec![(2, 19)]);
+        // a swap is two substitutions
+        assert!(automaton.find_all_approx("timeuot", 1).is_empty());
+        assert_eq!(automaton.find_all_approx("timeuot", 2), vec![(2, 0)]);
+        // no insertions or deletions
+        assert!(automaton.find_all_approx("eror", 1).is_empty());
+
+        // outputs reached through failure links are not reported twice
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        assert_eq!(
+            automaton.find_all_approx("ushers", 0),
+            vec![(1, 1), (0, 2), (3, 2)]
+        );
+        assert_eq!(automaton.find_all_approx("sha", 1), vec![(1, 0), (0, 1)]);
+    }
+}
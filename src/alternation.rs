@@ -0,0 +1,85 @@
+use crate::aho_corasick::AhoCorasick;
+
+/// Alternation search matches if any of several fixed strings occurs in the
+/// text, like the regular expression `a|b|c` restricted to literals. An empty
+/// list of alternatives never matches.
+///
+/// The alternatives are combined into a single Aho-Corasick automaton, so
+/// the text is scanned once no matter how many alternatives there are, and
+/// the scan stops as soon as any alternative ends.
+pub fn contains(alternatives: &[&str], text: &str) -> bool {
+    AltMatcher::new(alternatives).is_match(text)
+}
+
+/// An Aho-Corasick automaton over a set of alternatives, built once and
+/// reused to search many texts.
+pub struct AltMatcher {
+    automaton: AhoCorasick,
+}
+
+impl AltMatcher {
+    pub fn new(alternatives: &[&str]) -> Self {
+        Self {
+            automaton: AhoCorasick::new(alternatives),
+        }
+    }
+
+    /// Returns whether any alternative occurs in the text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.automaton.is_match(text)
+    }
+
+    /// Returns the index of the alternative that matches first and the char
+    /// index where it starts, as `(alternative, start)`. The match with the
+    /// leftmost start wins, even if another ends sooner; ties go to the
+    /// alternative listed first. An empty alternative matches at 0.
+    pub fn find_first(&self, text: &str) -> Option<(usize, usize)> {
+        self.automaton.find_first(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, AltMatcher};
+    use crate::knuth_morris_pratt;
+
+    #[test]
+    fn any_alternative_matches() {
+        let text = "the quick brown dog jumps over the lazy fox";
+        assert!(contains(&["cat", "dog", "fish"], text));
+        assert!(!contains(&["cat", "bird", "fish"], text));
+        assert!(!contains(&[], text));
+
+        // agrees with searching for each alternative on its own
+        let alternatives = ["lazy fox", "quick cat", "over", "jumped"];
+        for end in 0..=text.len() {
+            let text = &text[..end];
+            assert_eq!(
+                contains(&alternatives, text),
+                alternatives
+                    .iter()
+                    .any(|alt| knuth_morris_pratt::contains(alt, text)),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn find_first_returns_leftmost() {
+        let matcher = AltMatcher::new(&["fox", "dog", "lazy", "the"]);
+        let text = "the quick brown dog jumps over the lazy fox";
+        assert_eq!(matcher.find_first(text), Some((3, 0)));
+        assert_eq!(matcher.find_first("a lazy dog"), Some((2, 2)));
+        assert_eq!(matcher.find_first("no animals"), None);
+
+        // the longer alternative starts first, although it ends later
+        let matcher = AltMatcher::new(&["bcd", "abcdef"]);
+        assert_eq!(matcher.find_first("xabcdefx"), Some((1, 1)));
+        assert_eq!(matcher.find_first("xabcdxx"), Some((0, 2)));
+
+        // ties go to the alternative listed first
+        let matcher = AltMatcher::new(&["she", "sh", "he"]);
+        assert_eq!(matcher.find_first("ushers"), Some((0, 1)));
+        assert_eq!(AltMatcher::new(&["b", ""]).find_first("abc"), Some((1, 0)));
+    }
+}
@@ -0,0 +1,56 @@
+use crate::knuth_morris_pratt;
+
+/// Anchored search supports a small subset of regular expression syntax: a
+/// leading `^` requires the match to occur at the start of the text and a
+/// trailing `$` requires it to occur at the end. A pattern with both anchors
+/// must equal the text. Without anchors this is a plain substring search.
+/// There is no escaping, so a literal leading `^` or trailing `$` cannot be
+/// searched for.
+pub fn contains(pattern: &str, text: &str) -> bool {
+    let (pattern, at_start) = match pattern.strip_prefix('^') {
+        Some(rest) => (rest, true),
+        None => (pattern, false),
+    };
+
+    let (pattern, at_end) = match pattern.strip_suffix('$') {
+        Some(rest) => (rest, true),
+        None => (pattern, false),
+    };
+
+    match (at_start, at_end) {
+        (true, true) => text == pattern,
+        (true, false) => text.starts_with(pattern),
+        (false, true) => text.ends_with(pattern),
+        (false, false) => knuth_morris_pratt::contains(pattern, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::contains;
+
+    #[test]
+    fn start_anchor() {
+        assert!(contains("^abc", "abcdef"));
+        assert!(!contains("^abc", "xabcdef"));
+    }
+
+    #[test]
+    fn end_anchor() {
+        assert!(contains("abc$", "xyzabc"));
+        assert!(!contains("abc$", "xyzabcd"));
+    }
+
+    #[test]
+    fn both_anchors() {
+        assert!(contains("^abc$", "abc"));
+        assert!(!contains("^abc$", "abcabc"));
+        assert!(contains("^$", ""));
+    }
+
+    #[test]
+    fn unanchored() {
+        assert!(contains("abc", "xxabcxx"));
+        assert!(!contains("abd", "xxabcxx"));
+    }
+}
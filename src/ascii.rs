@@ -0,0 +1,87 @@
+/// Checks whether the pattern occurs in the text, working directly on bytes.
+/// This is the fast path for the common case of searching ASCII logs, since
+/// it skips decoding text into chars entirely.
+///
+/// Both inputs must be ASCII. Other input is not rejected in release builds,
+/// but a match may then start or end in the middle of a multi-byte char.
+///
+/// Uses Horspool's simplification of Boyer-Moore: after each alignment the
+/// pattern is shifted by the table entry for the text byte under its last
+/// position, so the shift table is a fixed array rather than a map.
+pub fn contains(pattern: &[u8], text: &[u8]) -> bool {
+    debug_assert!(pattern.is_ascii(), "pattern must be ASCII");
+    debug_assert!(text.is_ascii(), "text must be ASCII");
+
+    let m = pattern.len();
+    if m == 0 {
+        return true;
+    }
+    if m > text.len() {
+        return false;
+    }
+
+    let shifts = shift_table(pattern);
+    let last = m - 1;
+    let mut start = 0;
+    while start + m <= text.len() {
+        if &text[start..start + m] == pattern {
+            return true;
+        }
+        start += shifts[text[start + last] as usize];
+    }
+    false
+}
+
+/// Returns, for each byte, how far the pattern can move when that byte is
+/// aligned with its last position. Bytes that do not appear in the pattern
+/// (outside its last position) allow a shift past the whole pattern.
+fn shift_table(pattern: &[u8]) -> [usize; 256] {
+    let m = pattern.len();
+    let mut shifts = [m; 256];
+    for (i, &byte) in pattern[..m - 1].iter().enumerate() {
+        shifts[byte as usize] = m - 1 - i;
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, shift_table};
+
+    #[test]
+    fn contains_bytes() {
+        let text = b"2024-01-01 12:00:00 ERROR disk full on /dev/sda1";
+        assert!(contains(b"ERROR", text));
+        assert!(contains(b"sda1", text));
+        assert!(contains(b"2024", text));
+        assert!(contains(b"", text));
+        assert!(!contains(b"WARN", text));
+        assert!(!contains(b"sda2", text));
+        assert!(!contains(b"abc", b"ab"));
+        assert!(contains(b"aab", b"aaaab"));
+    }
+
+    #[test]
+    fn long_patterns() {
+        let pattern = b"the quick brown fox jumps";
+        let text = b"a log line mentioning the quick brown fox jumps over";
+        assert!(contains(pattern, text));
+        assert!(!contains(b"the quick brown fox jumped", text));
+    }
+
+    #[test]
+    fn shift_table_uses_rightmost_occurrence() {
+        let shifts = shift_table(b"abcab");
+        assert_eq!(shifts[b'a' as usize], 1);
+        assert_eq!(shifts[b'b' as usize], 3);
+        assert_eq!(shifts[b'c' as usize], 2);
+        assert_eq!(shifts[b'z' as usize], 5);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "text must be ASCII")]
+    fn rejects_non_ascii_in_debug() {
+        contains(b"a", "caf\u{e9}".as_bytes());
+    }
+}
@@ -0,0 +1,70 @@
+use std::{error::Error, fmt};
+
+use crate::alternation;
+
+/// A char in a DNA pattern other than `A`, `C`, `G` or `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidBase(pub char);
+
+impl fmt::Display for InvalidBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid DNA base {:?}, expected one of A, C, G or T",
+            self.0
+        )
+    }
+}
+
+impl Error for InvalidBase {}
+
+/// Checks whether a DNA pattern occurs in the text on either strand, i.e.
+/// whether the pattern or its reverse complement (the pattern reversed with
+/// `A` and `T`, and `C` and `G`, swapped) occurs. Both are searched for in a
+/// single pass. The pattern must be uppercase `ACGT`; the text is not checked.
+pub fn contains_with_revcomp(pattern: &str, text: &str) -> Result<bool, InvalidBase> {
+    let revcomp = reverse_complement(pattern)?;
+    Ok(alternation::contains(&[pattern, &revcomp], text))
+}
+
+fn reverse_complement(pattern: &str) -> Result<String, InvalidBase> {
+    pattern
+        .chars()
+        .rev()
+        .map(|base| match base {
+            'A' => Ok('T'),
+            'T' => Ok('A'),
+            'C' => Ok('G'),
+            'G' => Ok('C'),
+            other => Err(InvalidBase(other)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains_with_revcomp, reverse_complement, InvalidBase};
+
+    #[test]
+    fn matches_either_strand() {
+        assert_eq!(reverse_complement("GATTACA"), Ok("TGTAATC".to_string()));
+
+        let text = "CCCTGTAATCCC";
+        assert!(!text.contains("GATTACA"));
+        assert_eq!(contains_with_revcomp("GATTACA", text), Ok(true));
+        assert_eq!(contains_with_revcomp("CCCTG", text), Ok(true));
+        assert_eq!(contains_with_revcomp("GGGG", text), Ok(false));
+    }
+
+    #[test]
+    fn rejects_invalid_bases() {
+        assert_eq!(
+            contains_with_revcomp("GATNACA", "GATNACA"),
+            Err(InvalidBase('N'))
+        );
+        assert_eq!(
+            contains_with_revcomp("gattaca", "gattaca"),
+            Err(InvalidBase('a'))
+        );
+    }
+}
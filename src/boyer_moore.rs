@@ -0,0 +1,695 @@
+use std::{cmp::max, collections::HashMap};
+
+use crate::{knuth_morris_pratt, profile::Counters};
+
+/// Boyer-Moore string search starts comparison from the back of the pattern
+/// and uses heuristics to jump several characters at a time for each
+/// mismatch. It preprocesses the pattern using two rules to determine how
+/// much to shift based on the length of the match before failure: the
+/// bad-character rule and the good-suffix rule.
+///
+/// The bad-character rule focuses on the character in the text that failed
+/// to match. If it is not present in the pattern, then we can skip the full
+/// pattern length (since the match must occur after that character has been
+/// passed). If it is present in the pattern to the left of the mismatched
+/// position, then we can align the text occurrence and the pattern
+/// occurrence. This page has a good explanation of the bad-character rule:
+/// https://hyperskill.org/learn/step/35869.
+///
+/// The good-suffix rule focuses on the characters that are matched. If that
+/// suffix repeats itself in the pattern, then we can align the repetition
+/// with the text. We do this only when the repetition is at the beginning
+/// of the pattern or when the character preceding the repetition is not the
+/// same as the character that precedes the suffix (otherwise, the shift
+/// would fail again for the same reason). If the suffix does not repeat
+/// itself in the pattern, then we look for the longest suffix of the suffix
+/// that is also a prefix of the pattern and align on the prefix. If neither
+/// rule matches, we skip the full pattern length (since the suffix will not
+/// be found in the rest of the pattern). This page has a good explanation
+/// of the good-suffix rule: https://hyperskill.org/learn/step/36987.
+///
+/// The resulting algorithm runs in linear time in the average case, though
+/// it can decay to quadratic time as O(mn).
+pub fn contains(pattern: &str, text: &str) -> bool {
+    BoyerMoore::new(pattern).contains(text)
+}
+
+/// A pattern with its bad-character and good-suffix tables computed up
+/// front, so that it can be searched for in many texts without rebuilding
+/// them.
+pub struct BoyerMoore {
+    pattern: Vec<char>,
+    bad_character_table: HashMap<char, usize>,
+    good_suffix_table: Vec<usize>,
+}
+
+impl BoyerMoore {
+    pub fn new(pattern: &str) -> Self {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let bad_character_table = bad_character_table(&pattern);
+        let good_suffix_table = good_suffix_table(&pattern);
+        Self {
+            pattern,
+            bad_character_table,
+            good_suffix_table,
+        }
+    }
+
+    /// Checks whether the pattern occurs in the text.
+    pub fn contains(&self, text: &str) -> bool {
+        if self.pattern.is_empty() {
+            return true;
+        }
+
+        let text: Vec<char> = text.chars().collect();
+        find_from(
+            &self.pattern,
+            &text,
+            &self.bad_character_table,
+            &self.good_suffix_table,
+            0,
+            &mut Counters::default(),
+        )
+        .is_some()
+    }
+}
+
+/// Same as [`contains`], but reuses the given buffers as described in
+/// [`naive::contains_with_buffers`](crate::naive::contains_with_buffers).
+pub fn contains_with_buffers(
+    pattern: &str,
+    text: &str,
+    pattern_buf: &mut Vec<char>,
+    text_buf: &mut Vec<char>,
+) -> bool {
+    pattern_buf.clear();
+    pattern_buf.extend(pattern.chars());
+    text_buf.clear();
+    text_buf.extend(text.chars());
+    let (pattern, text) = (&pattern_buf[..], &text_buf[..]);
+
+    contains_counted(pattern, text, &mut Counters::default())
+}
+
+/// Runs the search over the collected chars, recording the work done in
+/// `counters`.
+pub(crate) fn contains_counted(pattern: &[char], text: &[char], counters: &mut Counters) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+
+    if text.is_empty() || text.len() < pattern.len() {
+        return false;
+    }
+
+    let bad_character_table = bad_character_table(pattern);
+    let good_suffix_table = good_suffix_table(pattern);
+
+    find_from(
+        pattern,
+        text,
+        &bad_character_table,
+        &good_suffix_table,
+        0,
+        counters,
+    )
+    .is_some()
+}
+
+/// Returns the char index of every match, including matches that overlap.
+/// After each match the scan resumes one position further on. An empty
+/// pattern matches at every char boundary.
+pub fn find_all(pattern: &str, text: &str) -> Vec<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return (0..=text.len()).collect();
+    }
+
+    let bad_character_table = bad_character_table(&pattern);
+    let good_suffix_table = good_suffix_table(&pattern);
+
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = find_from(
+        &pattern,
+        &text,
+        &bad_character_table,
+        &good_suffix_table,
+        start,
+        &mut Counters::default(),
+    ) {
+        found.push(pos);
+        start = pos + 1;
+    }
+    found
+}
+
+/// Returns the byte offset of the first match, so that
+/// `&text[offset..offset + pattern.len()]` is the matched slice. The search
+/// itself runs over chars, and the char index of the match is mapped back to
+/// a byte offset with `char_indices`. An empty pattern matches at 0.
+pub fn find_byte(pattern: &str, text: &str) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let pos = find_from(
+        &pattern,
+        &chars,
+        &bad_character_table(&pattern),
+        &good_suffix_table(&pattern),
+        0,
+        &mut Counters::default(),
+    )?;
+    text.char_indices().nth(pos).map(|(offset, _)| offset)
+}
+
+/// Same as [`contains`], but only reports matches that form a whole word,
+/// i.e. are not preceded or followed by an alphanumeric character. When a
+/// candidate fails the boundary check, the search resumes one position
+/// after it.
+pub fn contains_whole_word(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    if text.is_empty() || text.len() < pattern.len() {
+        return false;
+    }
+
+    let bad_character_table = bad_character_table(&pattern);
+    let good_suffix_table = good_suffix_table(&pattern);
+
+    let mut start = 0;
+    while let Some(pos) = find_from(
+        &pattern,
+        &text,
+        &bad_character_table,
+        &good_suffix_table,
+        start,
+        &mut Counters::default(),
+    ) {
+        let end = pos + pattern.len();
+        let left_boundary = pos == 0 || !text[pos - 1].is_alphanumeric();
+        let right_boundary = end == text.len() || !text[end].is_alphanumeric();
+        if left_boundary && right_boundary {
+            return true;
+        }
+        start = pos + 1;
+    }
+
+    false
+}
+
+/// Same as [`contains`], but every time the pattern is moved along the text
+/// it moves by at least `min_shift` positions, and reports the work done.
+/// This is meant for studying how the size of the shifts affects the number
+/// of comparisons, not for real searches: any value above 1 can skip past
+/// genuine matches. A `min_shift` of 0 or 1 gives the standard search.
+pub fn contains_min_shift(pattern: &str, text: &str, min_shift: usize) -> (bool, Counters) {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let mut counters = Counters::default();
+
+    if pattern.is_empty() || text.len() < pattern.len() {
+        return (pattern.is_empty(), counters);
+    }
+
+    let bad_character_table = bad_character_table(&pattern);
+    let good_suffix_table = good_suffix_table(&pattern);
+    let last = pattern.len() - 1;
+
+    let mut start = 0;
+    while start + pattern.len() <= text.len() {
+        let mut j = last;
+        loop {
+            counters.comparisons += 1;
+            if text[start + j] != pattern[j] {
+                break;
+            }
+
+            if j == 0 {
+                return (true, counters);
+            }
+            j -= 1;
+        }
+
+        let cursor = start + j;
+        let shift = cursor_shift(
+            &bad_character_table,
+            &good_suffix_table,
+            pattern.len(),
+            j,
+            text[cursor],
+        );
+        let next = (cursor + shift).saturating_sub(last);
+        start = max(next, start + min_shift.max(1));
+        counters.shifts += 1;
+    }
+
+    (false, counters)
+}
+
+/// A single step of a Boyer-Moore search, as reported by [`search_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchEvent {
+    /// The text char at `text_idx` was compared against the pattern char at
+    /// `pattern_idx`.
+    Compare {
+        text_idx: usize,
+        pattern_idx: usize,
+        equal: bool,
+    },
+    /// The pattern was moved `amount` positions along the text.
+    Shift { amount: usize },
+    /// The pattern matched the text starting at `start`.
+    Match { start: usize },
+}
+
+/// Same as [`contains`], but records every comparison and shift made along
+/// the way, ending with a [`SearchEvent::Match`] if the pattern is found.
+/// The events replay the search step by step, e.g. to animate it. Indices
+/// are char indices, and shifts are measured in alignments of the pattern
+/// rather than in moves of the text cursor. An empty pattern yields a
+/// single match at 0.
+pub fn search_events(pattern: &str, text: &str) -> Vec<SearchEvent> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return vec![SearchEvent::Match { start: 0 }];
+    }
+
+    let bad_character_table = bad_character_table(&pattern);
+    let good_suffix_table = good_suffix_table(&pattern);
+    let last = pattern.len() - 1;
+
+    let mut events = Vec::new();
+    let mut start = 0;
+    while start + pattern.len() <= text.len() {
+        let mut j = last;
+        loop {
+            let equal = text[start + j] == pattern[j];
+            events.push(SearchEvent::Compare {
+                text_idx: start + j,
+                pattern_idx: j,
+                equal,
+            });
+            if !equal {
+                break;
+            }
+
+            if j == 0 {
+                events.push(SearchEvent::Match { start });
+                return events;
+            }
+            j -= 1;
+        }
+
+        let cursor = start + j;
+        let shift = cursor_shift(
+            &bad_character_table,
+            &good_suffix_table,
+            pattern.len(),
+            j,
+            text[cursor],
+        );
+        let amount = cursor + shift - last - start;
+        events.push(SearchEvent::Shift { amount });
+        start += amount;
+    }
+
+    events
+}
+
+/// The result of [`contains_adaptive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adaptive {
+    /// Whether the pattern was found.
+    pub found: bool,
+    /// Whether the search switched to Knuth-Morris-Pratt.
+    pub fell_back: bool,
+}
+
+/// Boyer-Moore performs more than `FALLBACK_FACTOR` comparisons per text
+/// char only on inputs that drive it towards its quadratic worst case.
+const FALLBACK_FACTOR: usize = 3;
+
+/// Same as [`contains`], but guards against the quadratic worst case. The
+/// search starts out as Boyer-Moore and counts comparisons. If they exceed
+/// a small multiple of the text length, the rest of the text (from the
+/// alignment that was about to be checked) is searched with
+/// Knuth-Morris-Pratt, which is linear. Inputs that behave well never pay
+/// for building the KMP table.
+pub fn contains_adaptive(pattern: &str, text: &str) -> Adaptive {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() || text.len() < pattern.len() {
+        return Adaptive {
+            found: pattern.is_empty(),
+            fell_back: false,
+        };
+    }
+
+    let bad_character_table = bad_character_table(&pattern);
+    let good_suffix_table = good_suffix_table(&pattern);
+
+    let mut counters = Counters::default();
+    let result = find_from_bounded(
+        &pattern,
+        &text,
+        &bad_character_table,
+        &good_suffix_table,
+        0,
+        &mut counters,
+        FALLBACK_FACTOR * text.len(),
+    );
+
+    match result {
+        Ok(found) => Adaptive {
+            found: found.is_some(),
+            fell_back: false,
+        },
+        Err(start) => Adaptive {
+            found: knuth_morris_pratt::contains_counted(&pattern, &text[start..], &mut counters),
+            fell_back: true,
+        },
+    }
+}
+
+/// Returns the position of the first match that starts at or after
+/// `start`. The pattern must not be empty.
+fn find_from(
+    pattern: &[char],
+    text: &[char],
+    bad_character_table: &HashMap<char, usize>,
+    good_suffix_table: &[usize],
+    start: usize,
+    counters: &mut Counters,
+) -> Option<usize> {
+    let result = find_from_bounded(
+        pattern,
+        text,
+        bad_character_table,
+        good_suffix_table,
+        start,
+        counters,
+        usize::MAX,
+    );
+
+    match result {
+        Ok(found) => found,
+        Err(_) => unreachable!("comparisons cannot exceed usize::MAX"),
+    }
+}
+
+/// Same as [`find_from`], but gives up once more than `max_comparisons`
+/// comparisons have been counted, returning the start of the alignment
+/// that was about to be checked. No match starts before that position.
+fn find_from_bounded(
+    pattern: &[char],
+    text: &[char],
+    bad_character_table: &HashMap<char, usize>,
+    good_suffix_table: &[usize],
+    start: usize,
+    counters: &mut Counters,
+    max_comparisons: usize,
+) -> Result<Option<usize>, usize> {
+    let mut i = start + pattern.len() - 1;
+
+    while i < text.len() {
+        if counters.comparisons > max_comparisons {
+            return Err(i + 1 - pattern.len());
+        }
+
+        let mut j = pattern.len() - 1;
+        loop {
+            counters.comparisons += 1;
+            if text[i] != pattern[j] {
+                break;
+            }
+
+            if j == 0 {
+                return Ok(Some(i));
+            }
+            i -= 1;
+            j -= 1;
+        }
+
+        i += cursor_shift(
+            bad_character_table,
+            good_suffix_table,
+            pattern.len(),
+            j,
+            text[i],
+        );
+        counters.shifts += 1;
+    }
+
+    Ok(None)
+}
+
+/// Returns how far to move the text cursor after the pattern char at `j`
+/// failed to match the text char `mismatch`, taking the larger of the shifts
+/// given by the bad-character and good-suffix rules.
+fn cursor_shift(
+    bad_character_table: &HashMap<char, usize>,
+    good_suffix_table: &[usize],
+    pattern_len: usize,
+    j: usize,
+    mismatch: char,
+) -> usize {
+    let bad_char_shift = *bad_character_table.get(&mismatch).unwrap_or(&pattern_len);
+    let good_suffix_shift = good_suffix_table[pattern_len - j - 1];
+    max(bad_char_shift, good_suffix_shift)
+}
+
+fn bad_character_table(pattern: &[char]) -> HashMap<char, usize> {
+    let mut table = HashMap::new();
+    for (i, ch) in pattern.iter().enumerate() {
+        table.insert(*ch, pattern.len() - i - 1);
+    }
+    table
+}
+
+fn good_suffix_table(pattern: &[char]) -> Vec<usize> {
+    let mut table = vec![1]; // shift 1 if no matched suffix
+
+    for suffix_len in 1..pattern.len() {
+        let suffix = &pattern[pattern.len() - suffix_len..];
+        let mismatch = pattern[pattern.len() - suffix_len - 1];
+        let remainder = &pattern[..pattern.len() - 1];
+
+        table.push(pattern.len());
+
+        let mut found_full_suffix = false;
+
+        // try to find next occurrence of full suffix
+        for pos in 0..remainder.len() - suffix.len() + 1 {
+            if &remainder[pos..pos + suffix_len] == suffix
+                && (pos == 0 || remainder[pos - 1] != mismatch)
+            {
+                table[suffix_len] = pattern.len() - pos;
+                found_full_suffix = true;
+            }
+        }
+
+        if found_full_suffix {
+            continue;
+        }
+
+        // try to find longest partial suffix that matches prefix
+        for par_suffix_len in (1..suffix_len).rev() {
+            let prefix = &pattern[..par_suffix_len];
+            let par_suffix = &pattern[pattern.len() - par_suffix_len..];
+            if prefix == par_suffix {
+                table[suffix_len] = pattern.len() - par_suffix_len + suffix_len;
+                break;
+            }
+        }
+    }
+
+    table
+}
+
+#[test]
+fn compiled_pattern_reused() {
+    use crate::test::{TEST_CASES, TEST_PATTERN};
+
+    let matcher = BoyerMoore::new(TEST_PATTERN);
+    for (text, expected) in TEST_CASES {
+        assert_eq!(matcher.contains(text), expected, "{text}");
+    }
+
+    let matcher = BoyerMoore::new("");
+    assert!(matcher.contains(""));
+    assert!(matcher.contains("abc"));
+}
+
+#[test]
+fn bad_character_table_correct() {
+    let pattern: Vec<char> = "abac".chars().collect();
+    let table = bad_character_table(&pattern);
+    assert_eq!(table, HashMap::from([('a', 1), ('b', 2), ('c', 0)]));
+}
+
+#[test]
+fn matches_aligned_with_first_character() {
+    assert!(contains("abc", "xxabc"));
+    assert!(!contains("xbc", "abc"));
+    assert!(!contains("z", "abc"));
+}
+
+#[test]
+fn find_byte_slices_multibyte_text() {
+    let text = "héllo world";
+    let offset = find_byte("world", text).unwrap();
+    assert_eq!(offset, 7);
+    assert_eq!(text.chars().position(|ch| ch == 'w'), Some(6));
+    assert_eq!(&text[offset..offset + "world".len()], "world");
+
+    let offset = find_byte("llo", text).unwrap();
+    assert_eq!(&text[offset..offset + "llo".len()], "llo");
+    assert_eq!(find_byte("héllo", text), Some(0));
+    assert_eq!(find_byte("", text), Some(0));
+    assert_eq!(find_byte("moon", text), None);
+}
+
+#[test]
+fn whole_word_requires_boundaries() {
+    assert!(contains_whole_word("cat", "a cat sat"));
+    assert!(contains_whole_word("cat", "concatenate the cat"));
+    assert!(!contains_whole_word("cat", "category"));
+    assert!(!contains_whole_word("cat", "a bobcat"));
+}
+
+#[test]
+fn contains_adaptive_falls_back() {
+    let text = "a".repeat(1000);
+
+    let pattern = format!("b{}", "a".repeat(49));
+    let result = contains_adaptive(&pattern, &text);
+    assert!(result.fell_back);
+    assert!(!result.found);
+
+    let result = contains_adaptive(&pattern, &format!("{text}{pattern}"));
+    assert!(result.fell_back);
+    assert!(result.found);
+
+    let result = contains_adaptive("abcde", "12345abcde");
+    assert!(!result.fell_back);
+    assert!(result.found);
+}
+
+#[test]
+fn contains_min_shift_default_is_standard() {
+    use crate::test::{TEST_CASES, TEST_PATTERN};
+
+    for (text, expected) in TEST_CASES {
+        let (found, counters) = contains_min_shift(TEST_PATTERN, text, 1);
+        assert_eq!(found, expected, "{text}");
+        assert_eq!(found, contains(TEST_PATTERN, text));
+
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        let mut standard = Counters::default();
+        contains_counted(&chars(TEST_PATTERN), &chars(text), &mut standard);
+        assert_eq!(counters, standard, "{text}");
+    }
+
+    // a forced shift of two steps over the match at position 1
+    assert!(contains_min_shift("ab", "xab", 1).0);
+    assert!(!contains_min_shift("ab", "xab", 2).0);
+    assert_eq!(
+        contains_min_shift("ab", "xab", 0),
+        contains_min_shift("ab", "xab", 1)
+    );
+}
+
+#[test]
+fn search_events_replay_search() {
+    use SearchEvent::{Compare, Match, Shift};
+
+    // "b" mismatches "a", which occurs one position earlier in the pattern
+    assert_eq!(
+        search_events("ab", "aab"),
+        vec![
+            Compare {
+                text_idx: 1,
+                pattern_idx: 1,
+                equal: false,
+            },
+            Shift { amount: 1 },
+            Compare {
+                text_idx: 2,
+                pattern_idx: 1,
+                equal: true,
+            },
+            Compare {
+                text_idx: 1,
+                pattern_idx: 0,
+                equal: true,
+            },
+            Match { start: 1 },
+        ]
+    );
+    assert_eq!(search_events("", "abc"), vec![Match { start: 0 }]);
+    assert_eq!(search_events("abcd", "abc"), vec![]);
+
+    // the events account for the same work as the plain search
+    use crate::test::{TEST_CASES, TEST_PATTERN};
+    for (text, expected) in TEST_CASES {
+        let events = search_events(TEST_PATTERN, text);
+        let found = matches!(events.last(), Some(Match { .. }));
+        assert_eq!(found, expected, "{text}");
+
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        let mut counters = Counters::default();
+        contains_counted(&chars(TEST_PATTERN), &chars(text), &mut counters);
+        let compares = events
+            .iter()
+            .filter(|e| matches!(e, Compare { .. }))
+            .count();
+        let shifts = events.iter().filter(|e| matches!(e, Shift { .. })).count();
+        assert_eq!(
+            (compares, shifts),
+            (counters.comparisons, counters.shifts),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn short_patterns() {
+    // the scan for a repeated suffix covers `pattern.len() - suffix_len`
+    // positions, which is at least 1 since suffixes are proper
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    assert_eq!(good_suffix_table(&chars("a")), vec![1]);
+    assert_eq!(good_suffix_table(&chars("ab")), vec![1, 2]);
+    assert_eq!(good_suffix_table(&chars("aba")), vec![1, 3, 4]);
+
+    for pattern in ["a", "ab", "aba"] {
+        for text in ["", "a", "b", "ab", "ba", "aba", "abba", "xxabax", "babab"] {
+            assert_eq!(
+                contains(pattern, text),
+                text.contains(pattern),
+                "{pattern:?} in {text:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn good_suffix_table_correct() {
+    let pattern: Vec<char> = "bcacbcbc".chars().collect();
+    let table = good_suffix_table(&pattern);
+    assert_eq!(table, vec![1, 5, 8, 5, 10, 11, 12, 13]);
+}
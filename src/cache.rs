@@ -0,0 +1,87 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+use crate::knuth_morris_pratt;
+
+/// The number of patterns whose tables are kept.
+const CAPACITY: usize = 32;
+
+/// A pattern preprocessed for Knuth-Morris-Pratt search.
+struct Tables {
+    pattern: Vec<char>,
+    border_table: Vec<usize>,
+}
+
+/// Cached tables, most recently used first.
+static CACHE: Mutex<VecDeque<(String, Arc<Tables>)>> = Mutex::new(VecDeque::new());
+
+static HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Same as [`knuth_morris_pratt::contains`], but keeps the preprocessed
+/// tables of recently searched patterns in a global cache shared by all
+/// threads, so a hot pattern is only preprocessed once. The cache holds the
+/// tables for a fixed number of patterns and evicts the least recently used.
+pub fn contains_cached(pattern: &str, text: &str) -> bool {
+    let tables = tables(pattern);
+    knuth_morris_pratt::find(&tables.pattern, &tables.border_table, text).is_some()
+}
+
+/// Returns the number of times [`contains_cached`] has found a pattern's
+/// tables in the cache.
+pub fn hits() -> usize {
+    HITS.load(Ordering::Relaxed)
+}
+
+fn tables(pattern: &str) -> Arc<Tables> {
+    // the cache is left consistent between statements, so a panic in another
+    // thread cannot corrupt it
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(i) = cache.iter().position(|(key, _)| key == pattern) {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        let entry = cache.remove(i).expect("position is in bounds");
+        let tables = Arc::clone(&entry.1);
+        cache.push_front(entry);
+        return tables;
+    }
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let tables = Arc::new(Tables {
+        border_table: knuth_morris_pratt::border_table(&chars),
+        pattern: chars,
+    });
+
+    cache.push_front((pattern.to_string(), Arc::clone(&tables)));
+    cache.truncate(CAPACITY);
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{contains_cached, hits, tables, CAPACITY};
+
+    // a single test, since tests running in parallel would share the cache
+    #[test]
+    fn reuses_and_evicts_tables() {
+        let pattern = "cache-reuse-pattern";
+        let before = hits();
+        assert!(contains_cached(pattern, "a cache-reuse-pattern here"));
+        assert!(!contains_cached(pattern, "no match here"));
+        assert_eq!(hits(), before + 1);
+        assert!(Arc::ptr_eq(&tables(pattern), &tables(pattern)));
+
+        let first = tables("cache-evict-0");
+        for i in 1..=CAPACITY {
+            tables(&format!("cache-evict-{i}"));
+        }
+        assert!(!Arc::ptr_eq(&first, &tables("cache-evict-0")));
+        assert!(contains_cached("cache-evict-0", "xxcache-evict-0"));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+/// A deterministic finite automaton that recognizes texts containing a
+/// pattern. State `j` means the last `j` chars read are the first `j` chars of
+/// the pattern, so the state equal to the pattern length accepts.
+///
+/// This is the automaton that Knuth-Morris-Pratt simulates with its partial
+/// match table: here every transition is worked out up front, so each text
+/// char costs exactly one lookup. Only chars that appear in the pattern have
+/// transitions; any other char leads back to state 0.
+pub struct Dfa {
+    transitions: Vec<HashMap<char, usize>>,
+}
+
+impl Dfa {
+    pub fn new(pattern: &str) -> Self {
+        let pattern: Vec<char> = pattern.chars().collect();
+
+        let mut transitions = vec![HashMap::new(); pattern.len() + 1];
+
+        // `restart` is the state reached by the current state's longest proper
+        // border, from which a mismatch behaves the same
+        let mut restart = 0;
+        for (j, &ch) in pattern.iter().enumerate() {
+            if j > 0 {
+                transitions[j] = transitions[restart].clone();
+                restart = transitions[restart].get(&ch).copied().unwrap_or(0);
+            }
+            transitions[j].insert(ch, j + 1);
+        }
+
+        // after a match, carry on as if from the longest border of the pattern
+        if !pattern.is_empty() {
+            transitions[pattern.len()] = transitions[restart].clone();
+        }
+
+        Self { transitions }
+    }
+
+    /// Returns the state reached from `state` by reading `ch`.
+    pub fn next_state(&self, state: usize, ch: char) -> usize {
+        self.transitions[state].get(&ch).copied().unwrap_or(0)
+    }
+
+    /// Returns the accepting state, which is the pattern length.
+    pub fn accepting(&self) -> usize {
+        self.transitions.len() - 1
+    }
+
+    pub fn contains(&self, text: &str) -> bool {
+        let mut state = 0;
+        if state == self.accepting() {
+            return true;
+        }
+
+        for ch in text.chars() {
+            state = self.next_state(state, ch);
+            if state == self.accepting() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Runs the automaton over the whole text, calling `observer` with each
+    /// state entered and the char that led to it, and returns whether the
+    /// accepting state was ever reached. This makes it possible to log the
+    /// states or to build other matchers on top of the transitions.
+    pub fn run_with<F: FnMut(usize, char)>(&self, text: &str, mut observer: F) -> bool {
+        let mut state = 0;
+        let mut accepted = state == self.accepting();
+        for ch in text.chars() {
+            state = self.next_state(state, ch);
+            observer(state, ch);
+            accepted |= state == self.accepting();
+        }
+        accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dfa;
+
+    #[test]
+    fn observes_state_sequence() {
+        let dfa = Dfa::new("abab");
+
+        let mut states = Vec::new();
+        let found = dfa.run_with("aababab", |state, ch| states.push((state, ch)));
+        assert!(found);
+        assert_eq!(
+            states,
+            vec![
+                (1, 'a'),
+                (1, 'a'),
+                (2, 'b'),
+                (3, 'a'),
+                (4, 'b'),
+                // the match continues from its border "ab"
+                (3, 'a'),
+                (4, 'b'),
+            ]
+        );
+
+        assert!(!dfa.run_with("abba", |_, _| {}));
+    }
+
+    #[test]
+    fn contains_matches_kmp() {
+        use crate::test::{TEST_CASES, TEST_PATTERN};
+
+        let dfa = Dfa::new(TEST_PATTERN);
+        for (text, expected) in TEST_CASES {
+            assert_eq!(dfa.contains(text), expected, "{text}");
+        }
+
+        assert!(Dfa::new("aab").contains("aaab"));
+        assert!(Dfa::new("").contains(""));
+        assert!(!Dfa::new("a").contains(""));
+    }
+}
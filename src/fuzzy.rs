@@ -0,0 +1,169 @@
+/// A single step in transforming the pattern into the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    /// The pattern char equals the text char.
+    Match(char),
+    /// The pattern char (first) is replaced by the text char (second).
+    Substitute(char, char),
+    /// The text char is missing from the pattern.
+    Insert(char),
+    /// The pattern char is missing from the text.
+    Delete(char),
+}
+
+/// Checks whether the pattern occurs in the text with at most `max_edits`
+/// edits, where an edit is substituting, inserting or deleting a single char
+/// as in [`align`]. So `"abcde"` occurs in `"abxde"` with one substitution,
+/// in `"abde"` with one deletion and in `"abcxde"` with one insertion. With
+/// no edits allowed this is an exact search.
+///
+/// Uses Sellers' variant of the edit distance table, which keeps one column
+/// per text char. Entry `i` of the column is the fewest edits that turn the
+/// first `i` pattern chars into some substring of the text ending at the
+/// current char; the top entry is always 0, since a match may start anywhere.
+/// This takes O(mn) time and O(m) space.
+pub fn contains_within(pattern: &str, text: &str, max_edits: usize) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // deleting the whole pattern leaves the empty string, which always occurs
+    let mut column: Vec<usize> = (0..=pattern.len()).collect();
+    if column[pattern.len()] <= max_edits {
+        return true;
+    }
+
+    for ch in text.chars() {
+        let mut diagonal = column[0];
+        for i in 1..=pattern.len() {
+            let cost = usize::from(pattern[i - 1] != ch);
+            let distance = (diagonal + cost).min(column[i] + 1).min(column[i - 1] + 1);
+            diagonal = column[i];
+            column[i] = distance;
+        }
+
+        if column[pattern.len()] <= max_edits {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Aligns the pattern against a window of text, returning the edit operations
+/// that turn the pattern into the text with the fewest substitutions,
+/// insertions and deletions (the Levenshtein distance). This is useful for
+/// highlighting exactly where a fuzzy match differs.
+///
+/// The distances between all prefixes of the two strings are computed with
+/// dynamic programming in O(mn) time and space, then the cheapest path is
+/// traced back from the full strings to the empty ones. When several paths
+/// are equally cheap, matches and substitutions are preferred over deletions,
+/// and deletions over insertions.
+pub fn align(pattern: &str, text_window: &str) -> Vec<EditOp> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text_window.chars().collect();
+
+    // distances[i][j] is the distance between pattern[..i] and text[..j]
+    let mut distances = vec![vec![0; text.len() + 1]; pattern.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=pattern.len() {
+        for j in 1..=text.len() {
+            let cost = usize::from(pattern[i - 1] != text[j - 1]);
+            distances[i][j] = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (pattern.len(), text.len());
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let cost = usize::from(pattern[i - 1] != text[j - 1]);
+            if distances[i][j] == distances[i - 1][j - 1] + cost {
+                ops.push(if cost == 0 {
+                    EditOp::Match(pattern[i - 1])
+                } else {
+                    EditOp::Substitute(pattern[i - 1], text[j - 1])
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if i > 0 && distances[i][j] == distances[i - 1][j] + 1 {
+            ops.push(EditOp::Delete(pattern[i - 1]));
+            i -= 1;
+        } else {
+            ops.push(EditOp::Insert(text[j - 1]));
+            j -= 1;
+        }
+    }
+
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align, contains_within, EditOp::*};
+
+    #[test]
+    fn align_kitten_sitting() {
+        assert_eq!(
+            align("kitten", "sitting"),
+            vec![
+                Substitute('k', 's'),
+                Match('i'),
+                Match('t'),
+                Match('t'),
+                Substitute('e', 'i'),
+                Match('n'),
+                Insert('g'),
+            ]
+        );
+    }
+
+    #[test]
+    fn align_deletions_and_empty() {
+        assert_eq!(
+            align("abcd", "ad"),
+            vec![Match('a'), Delete('b'), Delete('c'), Match('d')]
+        );
+        assert_eq!(align("", "ab"), vec![Insert('a'), Insert('b')]);
+        assert_eq!(align("ab", ""), vec![Delete('a'), Delete('b')]);
+        assert!(align("", "").is_empty());
+    }
+
+    #[test]
+    fn contains_within_edits() {
+        use crate::test::{TEST_CASES, TEST_PATTERN};
+
+        // no edits is an exact search
+        for (text, expected) in TEST_CASES {
+            assert_eq!(contains_within(TEST_PATTERN, text, 0), expected, "{text}");
+        }
+        assert!(!contains_within("abcde", "abxde", 0));
+
+        // one substitution, deletion or insertion
+        assert!(contains_within("abcde", "abxde", 1));
+        assert!(contains_within("abcde", "xxabdexx", 1));
+        assert!(contains_within("abcde", "abcxde", 1));
+        assert!(!contains_within("abcde", "axcxe", 1));
+
+        // two edits
+        assert!(contains_within("abcde", "axcxe", 2));
+        assert!(contains_within("abcde", "bcd", 2));
+        assert!(!contains_within("abcde", "bxd", 2));
+
+        assert!(contains_within("", "", 0));
+        assert!(contains_within("ab", "", 2));
+        assert!(!contains_within("ab", "", 1));
+    }
+}
@@ -1,39 +1,579 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    thread,
+};
 
-struct Index {
-    inner: HashMap<&'static str, Vec<usize>>,
+use crate::token;
+
+pub struct Index {
+    /// The `(doc, position)` of every occurrence of each word, sorted, where
+    /// `position` counts the words of the document before it.
+    inner: HashMap<String, Vec<(usize, usize)>>,
+    lengths: Vec<usize>,
+    /// The ids of documents that have been removed. Their lengths are zeroed
+    /// and they are left out of the document count.
+    removed: HashSet<usize>,
+    /// Words with fewer chars than this are neither indexed nor queried.
+    min_token_len: usize,
+    /// Whether words are lowercased before they are indexed or queried.
+    case_insensitive: bool,
 }
 
 impl Index {
-    fn new(corpus: &[&'static str]) -> Self {
-        let mut inner: HashMap<&'static str, Vec<usize>> = HashMap::new();
+    /// Indexes the documents in the order given, numbering them from 0. The
+    /// postings own their words, so the corpus only needs to live for the
+    /// duration of the call.
+    pub fn new(corpus: &[&str]) -> Self {
+        Self::build(corpus.iter().copied())
+    }
 
-        for (i, line) in corpus.iter().enumerate() {
-            line.split_ascii_whitespace()
-                .for_each(|word| match inner.get_mut(word) {
-                    Some(occurrences) => occurrences.push(i),
-                    None => {
-                        inner.insert(word, vec![i]);
-                    }
+    /// Builds the same index as [`Index::new`], but splits the corpus into one
+    /// contiguous chunk per available thread and indexes the chunks in
+    /// parallel. The partial postings are merged in chunk order with each
+    /// chunk's document ids offset by its position, so the merged postings
+    /// remain sorted by document id.
+    pub fn new_parallel(corpus: &[&str]) -> Self {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_len = corpus.len().div_ceil(threads).max(1);
+
+        let partials: Vec<Index> = thread::scope(|scope| {
+            let handles: Vec<_> = corpus
+                .chunks(chunk_len)
+                .map(|chunk| scope.spawn(|| Self::build(chunk.iter().copied())))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("indexing thread panicked"))
+                .collect()
+        });
+
+        let mut index = Self::empty(0);
+        index.lengths.reserve(corpus.len());
+        for partial in partials {
+            let offset = index.lengths.len();
+            index.merge(partial, offset);
+        }
+        index
+    }
+
+    /// Folds the postings of `other` into this index, adding `doc_offset` to
+    /// each of its document ids. The ids of `other` may come after every
+    /// existing id or fall among them, and each shared posting list is merged
+    /// in sorted order either way. Any ids skipped over are left as empty
+    /// documents. An id present in both indexes is treated as one document
+    /// holding the words of both, which is only useful when one side is empty
+    /// or removed there. Panics if the indexes were built with different
+    /// tokenizing options, since their keys could not be queried alike.
+    pub fn merge(&mut self, other: Index, doc_offset: usize) {
+        assert!(
+            self.case_insensitive == other.case_insensitive
+                && self.min_token_len == other.min_token_len,
+            "merged indexes must use the same tokenizing options"
+        );
+
+        for (word, occurrences) in other.inner {
+            let occurrences: Vec<(usize, usize)> = occurrences
+                .into_iter()
+                .map(|(doc, position)| (doc + doc_offset, position))
+                .collect();
+            match self.inner.get_mut(&word) {
+                Some(existing) => merge_postings(existing, occurrences),
+                None => {
+                    self.inner.insert(word, occurrences);
+                }
+            }
+        }
+
+        let previous_len = self.lengths.len();
+        let len = previous_len.max(doc_offset + other.lengths.len());
+        self.lengths.resize(len, 0);
+        for (doc, length) in other.lengths.into_iter().enumerate() {
+            let target = doc + doc_offset;
+            self.lengths[target] += length;
+            // a document is removed only if it is removed on every side that has it
+            if !other.removed.contains(&doc) {
+                self.removed.remove(&target);
+            } else if target >= previous_len {
+                self.removed.insert(target);
+            }
+        }
+    }
+
+    /// Builds the same index as [`Index::new`], but leaves out words with
+    /// fewer than `min_len` chars, which are mostly noise such as `"in"` or
+    /// `"a"`. Such words are also ignored in queries. Document lengths still
+    /// count every word.
+    pub fn with_min_token_len(corpus: &[&str], min_len: usize) -> Self {
+        let mut index = Self::empty(min_len);
+        index.extend(corpus.iter().copied());
+        index
+    }
+
+    /// Builds the same index as [`Index::new`], but lowercases words before
+    /// indexing them and lowercases queries before looking them up, so
+    /// `"cats"`, `"Cats"` and `"CATS"` all find the same documents.
+    pub fn new_case_insensitive(corpus: &[&str]) -> Self {
+        let mut index = Self::empty(0);
+        index.case_insensitive = true;
+        index.extend(corpus.iter().copied());
+        index
+    }
+
+    fn empty(min_token_len: usize) -> Self {
+        Self {
+            inner: HashMap::new(),
+            lengths: Vec::new(),
+            removed: HashSet::new(),
+            min_token_len,
+            case_insensitive: false,
+        }
+    }
+
+    fn build<'a>(corpus: impl Iterator<Item = &'a str>) -> Self {
+        let mut index = Self::empty(0);
+        index.extend(corpus);
+        index
+    }
+
+    /// Removes every document, keeping the allocated capacity so the index
+    /// can be refilled with [`Extend::extend`] without growing from scratch.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.lengths.clear();
+        self.removed.clear();
+    }
+
+    /// Removes document `doc` from every posting list and drops the words
+    /// left without postings. The other documents keep their ids, and new
+    /// documents are still numbered after the last one ever added, so `doc`
+    /// is not reused. It no longer counts towards [`Index::is_empty`] or
+    /// [`Index::average_document_length`], and its length reads as zero.
+    /// Removing an unknown or already removed document does nothing.
+    ///
+    /// The postings own their words, so a word can be dropped without
+    /// borrowing the corpus again; the cost is one allocation per distinct
+    /// word when indexing.
+    pub fn remove(&mut self, doc: usize) {
+        if doc >= self.lengths.len() || !self.removed.insert(doc) {
+            return;
+        }
+        self.lengths[doc] = 0;
+
+        self.inner.retain(|_, occurrences| {
+            occurrences.retain(|&(occurrence, _)| occurrence != doc);
+            !occurrences.is_empty()
+        });
+    }
+
+    /// Returns whether the index holds no documents, not counting removed
+    /// ones.
+    pub fn is_empty(&self) -> bool {
+        self.live_documents() == 0
+    }
+
+    /// Returns the number of documents, not counting removed ones.
+    fn live_documents(&self) -> usize {
+        self.lengths.len() - self.removed.len()
+    }
+
+    /// Returns the document of every occurrence of `word`, in ascending
+    /// order. The query is split into words like the documents, so
+    /// `"often,"` finds `"often"`, and a query that splits into several words,
+    /// such as `"dawn's"`, finds them as a phrase.
+    pub fn find(&self, word: &str) -> Option<Vec<usize>> {
+        self.postings(word)
+            .map(|occurrences| docs(&occurrences).collect())
+    }
+
+    /// Same as [`Index::find`], but yields the document ids lazily from the
+    /// stored postings instead of cloning them, so a caller that only needs
+    /// the first few can stop early. Yields nothing if the word is absent.
+    pub fn find_iter<'a>(&'a self, word: &str) -> impl Iterator<Item = usize> + 'a {
+        self.postings(word)
+            .into_iter()
+            .flat_map(|occurrences| (0..occurrences.len()).map(move |i| occurrences[i].0))
+    }
+
+    /// Returns the number of distinct documents containing `word`.
+    pub fn count_docs(&self, word: &str) -> usize {
+        match self.postings(word) {
+            Some(occurrences) => {
+                // postings are sorted, so repeats of a document are adjacent
+                1 + occurrences
+                    .windows(2)
+                    .filter(|pair| pair[0].0 != pair[1].0)
+                    .count()
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns whether some document contains all of the words. The words are
+    /// checked rarest first: the documents of the rarest word are the only
+    /// candidates, and each is looked up in the postings of the other words
+    /// until one contains them all. Words too short to be indexed are skipped,
+    /// and an empty list is satisfied by any document.
+    pub fn exists(&self, words: &[String]) -> bool {
+        let mut words: Vec<&str> = words
+            .iter()
+            .map(String::as_str)
+            .filter(|word| token::words(word).any(|word| self.is_indexed(word)))
+            .collect();
+        words.sort_by_cached_key(|word| self.count_docs(word));
+
+        let (rarest, rest) = match words.split_first() {
+            Some(split) => split,
+            None => return !self.is_empty(),
+        };
+
+        let rest: Vec<Cow<[(usize, usize)]>> =
+            match rest.iter().map(|word| self.postings(word)).collect() {
+                Some(rest) => rest,
+                None => return false,
+            };
+
+        self.find_iter(rarest).any(|doc| {
+            rest.iter()
+                .all(|occurrences| contains_doc(occurrences, doc))
+        })
+    }
+
+    /// Looks up the postings of a query, split into words like the documents.
+    /// A query of several words gives the postings of the phrase, at the
+    /// position of its first word.
+    fn postings(&self, word: &str) -> Option<Cow<'_, [(usize, usize)]>> {
+        let words: Vec<&str> = token::words(word).collect();
+        match words.as_slice() {
+            [] => None,
+            [word] => self
+                .inner
+                .get(self.key(word).as_ref())
+                .map(|occurrences| Cow::Borrowed(occurrences.as_slice())),
+            _ => {
+                let found = self.phrase_postings(&words);
+                (!found.is_empty()).then_some(Cow::Owned(found))
+            }
+        }
+    }
+
+    /// Returns the key `word` is stored under, which is the word itself
+    /// unless the index is case-insensitive.
+    fn key<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        if self.case_insensitive {
+            Cow::Owned(word.to_lowercase())
+        } else {
+            Cow::Borrowed(word)
+        }
+    }
+
+    /// Returns whether `word` is long enough to be indexed.
+    fn is_indexed(&self, word: &str) -> bool {
+        word.chars().count() >= self.min_token_len
+    }
+
+    /// Returns the documents that contain every term, in ascending order. The
+    /// sorted document lists of the terms are intersected pairwise by merging
+    /// them in linear time. No terms match no documents.
+    pub fn query_and(&self, terms: &[&str]) -> Vec<usize> {
+        let (first, rest) = match terms.split_first() {
+            Some(split) => split,
+            None => return Vec::new(),
+        };
+
+        rest.iter().fold(self.doc_ids(first), |found, term| {
+            intersect(&found, &self.doc_ids(term))
+        })
+    }
+
+    /// Returns the documents that contain at least one of the terms, in
+    /// ascending order, merging the sorted document lists in linear time.
+    pub fn query_or(&self, terms: &[&str]) -> Vec<usize> {
+        terms
+            .iter()
+            .fold(Vec::new(), |found, term| union(&found, &self.doc_ids(term)))
+    }
+
+    /// Returns the documents below `universe_size` that do not contain
+    /// `term`, in ascending order. The universe is given explicitly so that
+    /// removed documents can be left out of it.
+    pub fn query_not(&self, term: &str, universe_size: usize) -> Vec<usize> {
+        let excluded = self.doc_ids(term);
+        let mut excluded = excluded.iter().peekable();
+        (0..universe_size)
+            .filter(|doc| excluded.next_if(|&next| next <= doc) != Some(doc))
+            .collect()
+    }
+
+    /// Returns the distinct documents containing `word`, in ascending order.
+    fn doc_ids(&self, word: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = self.find_iter(word).collect();
+        found.dedup();
+        found
+    }
+
+    /// Returns the documents containing `word` that are also in `allowed`, in
+    /// ascending order. When the filter is smaller than the postings, each
+    /// allowed document is looked up in the postings instead of scanning them.
+    pub fn find_within(&self, word: &str, allowed: &HashSet<usize>) -> Vec<usize> {
+        let occurrences = match self.postings(word) {
+            Some(occurrences) => occurrences,
+            None => return Vec::new(),
+        };
+
+        let mut found: Vec<usize> = if allowed.len() < occurrences.len() {
+            let mut found: Vec<usize> = allowed
+                .iter()
+                .copied()
+                .filter(|&doc| contains_doc(&occurrences, doc))
+                .collect();
+            found.sort_unstable();
+            found
+        } else {
+            docs(&occurrences)
+                .filter(|doc| allowed.contains(doc))
+                .collect()
+        };
+        found.dedup();
+        found
+    }
+
+    /// Returns the documents in which the words of `phrase` occur one after
+    /// another, in ascending order. Punctuation is ignored, so a phrase may
+    /// run across the end of one sentence into the next. Words too short to
+    /// be indexed may be anything but still take up their position. A phrase
+    /// with no indexed words matches no documents.
+    pub fn find_phrase(&self, phrase: &str) -> Vec<usize> {
+        let words: Vec<&str> = token::words(phrase).collect();
+        let mut found: Vec<usize> = docs(&self.phrase_postings(&words)).collect();
+        found.dedup();
+        found
+    }
+
+    /// Returns the `(doc, position)` of every occurrence of the words one
+    /// after another, where `position` is that of the first word.
+    fn phrase_postings(&self, words: &[&str]) -> Vec<(usize, usize)> {
+        // each indexed word with its offset from the start of the phrase
+        let mut indexed = Vec::new();
+        for (offset, &word) in words.iter().enumerate() {
+            if !self.is_indexed(word) {
+                continue;
+            }
+            match self.inner.get(self.key(word).as_ref()) {
+                Some(occurrences) => indexed.push((offset, occurrences)),
+                None => return Vec::new(),
+            }
+        }
+
+        let ((first_offset, first), rest) = match indexed.split_first() {
+            Some(split) => split,
+            None => return Vec::new(),
+        };
+
+        first
+            .iter()
+            .filter(|&&(_, position)| position >= *first_offset)
+            .map(|&(doc, position)| (doc, position - first_offset))
+            .filter(|&(doc, start)| {
+                rest.iter().all(|(offset, occurrences)| {
+                    occurrences.binary_search(&(doc, start + offset)).is_ok()
                 })
+            })
+            .collect()
+    }
+
+    /// Returns the documents in which the words occur in the given order with
+    /// at most `max_total_gap` other words between them in all, in ascending
+    /// order. A gap of zero is the same as [`Index::find_phrase`], so
+    /// `["the", "sun"]` with a gap of 1 also matches `"the bright sun"`.
+    /// Words too short to be indexed are skipped, and an empty list of words
+    /// matches no documents.
+    ///
+    /// Each occurrence of the first word is extended by the earliest
+    /// occurrence of each later word after the one before it, found by binary
+    /// search in its postings. Taking the earliest never makes the total gap
+    /// larger, since the gap is just the distance from the first word to the
+    /// last.
+    pub fn find_phrase_tolerant(&self, words: &[&str], max_total_gap: usize) -> Vec<usize> {
+        let mut postings = Vec::with_capacity(words.len());
+        for &word in words {
+            if !self.is_indexed(word) {
+                continue;
+            }
+            match self.inner.get(self.key(word).as_ref()) {
+                Some(occurrences) => postings.push(occurrences),
+                None => return Vec::new(),
+            }
+        }
+
+        let (first, rest) = match postings.split_first() {
+            Some(split) => split,
+            None => return Vec::new(),
+        };
+
+        let mut found: Vec<usize> = first
+            .iter()
+            .filter(|&&(doc, start)| {
+                let mut previous = start;
+                for occurrences in rest {
+                    let next = occurrences.partition_point(|&occ| occ <= (doc, previous));
+                    match occurrences.get(next) {
+                        Some(&(next_doc, position)) if next_doc == doc => previous = position,
+                        _ => return false,
+                    }
+                }
+                previous - start - rest.len() <= max_total_gap
+            })
+            .map(|&(doc, _)| doc)
+            .collect();
+        found.dedup();
+        found
+    }
+
+    /// Returns the number of words in document `doc`, for use in length
+    /// normalization when ranking. A removed document has length zero. Panics
+    /// if `doc` is out of range.
+    pub fn document_length(&self, doc: usize) -> usize {
+        self.lengths[doc]
+    }
+
+    /// Returns the mean number of words per document, leaving out removed
+    /// documents, or zero if the index holds no documents.
+    pub fn average_document_length(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
         }
 
-        Self { inner }
+        // removed documents have their lengths zeroed
+        let total: usize = self.lengths.iter().sum();
+        total as f64 / self.live_documents() as f64
     }
+}
+
+/// Merges the sorted postings `other` into `postings`, keeping them sorted.
+/// When every occurrence in `other` comes after the existing ones, as when
+/// merging shards in order, they are simply appended.
+fn merge_postings(postings: &mut Vec<(usize, usize)>, other: Vec<(usize, usize)>) {
+    if postings.last() < other.first() {
+        postings.extend(other);
+        return;
+    }
+
+    let existing = std::mem::take(postings);
+    postings.reserve(existing.len() + other.len());
+    let (mut a, mut b) = (
+        existing.into_iter().peekable(),
+        other.into_iter().peekable(),
+    );
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+        let next = if x <= y { a.next() } else { b.next() };
+        postings.extend(next);
+    }
+    postings.extend(a);
+    postings.extend(b);
+}
+
+/// Returns the document of each occurrence, keeping repeats.
+fn docs(occurrences: &[(usize, usize)]) -> impl Iterator<Item = usize> + '_ {
+    occurrences.iter().map(|&(doc, _)| doc)
+}
+
+/// Returns whether some occurrence is in document `doc`.
+fn contains_doc(occurrences: &[(usize, usize)], doc: usize) -> bool {
+    occurrences
+        .binary_search_by_key(&doc, |&(occurrence, _)| occurrence)
+        .is_ok()
+}
+
+/// Returns the documents in both sorted lists by walking them side by side.
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut found = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                found.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    found
+}
 
-    fn find(&self, word: &str) -> Option<Vec<usize>> {
-        match self.inner.get(word) {
-            Some(occurrences) => Some(occurrences.clone()),
-            None => None,
+/// Returns the documents in either sorted list by walking them side by side.
+fn union(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut found = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                found.push(a[i]);
+                i += 1;
+            }
+            Ordering::Greater => {
+                found.push(b[j]);
+                j += 1;
+            }
+            Ordering::Equal => {
+                found.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    found.extend_from_slice(&a[i..]);
+    found.extend_from_slice(&b[j..]);
+    found
+}
+
+/// Builds an index over documents loaded at runtime. Postings own their keys,
+/// so the documents do not need to outlive the index.
+impl FromIterator<String> for Index {
+    fn from_iter<I: IntoIterator<Item = String>>(docs: I) -> Self {
+        let docs: Vec<String> = docs.into_iter().collect();
+        Self::build(docs.iter().map(String::as_str))
+    }
+}
+
+/// Adds documents after those already indexed, numbering them from the
+/// current document count.
+impl<'a> Extend<&'a str> for Index {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, docs: I) {
+        for line in docs {
+            let i = self.lengths.len();
+            self.lengths.push(token::words(line).count());
+
+            for (position, word) in token::words(line).enumerate() {
+                if !self.is_indexed(word) {
+                    continue;
+                }
+
+                let word = self.key(word);
+                match self.inner.get_mut(word.as_ref()) {
+                    Some(occurrences) => occurrences.push((i, position)),
+                    None => {
+                        self.inner.insert(word.into_owned(), vec![(i, position)]);
+                    }
+                }
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::Index;
+    use crate::token;
 
-    const CORPUS: [&'static str; 10] = [
+    const CORPUS: [&str; 10] = [
         "Cats nap often, basking in warm spots.",
         "Raindrops patter softly on windowpanes.",
         "Stars twinkle brightly in the night.",
@@ -59,4 +599,376 @@ mod tests {
         let in_occ = index.find("the");
         assert_eq!(in_occ, Some(vec![2, 8, 9]));
     }
+
+    #[test]
+    fn document_lengths() {
+        let index = Index::new(&CORPUS);
+
+        for (i, line) in CORPUS.iter().enumerate() {
+            assert_eq!(index.document_length(i), token::words(line).count());
+        }
+        assert_eq!(index.document_length(0), 7);
+        assert_eq!(index.document_length(1), 5);
+
+        assert_eq!(index.average_document_length(), 6.2);
+        assert_eq!(Index::new(&[]).average_document_length(), 0.0);
+    }
+
+    #[test]
+    fn from_owned_strings() {
+        let docs: Vec<String> = CORPUS.iter().map(|line| line.to_string()).collect();
+        let index = Index::from_iter(docs);
+
+        assert_eq!(index.find("in"), Some(vec![0, 2, 7]));
+        assert_eq!(index.find("the"), Some(vec![2, 8, 9]));
+        assert_eq!(index.find("moon"), None);
+        assert_eq!(index.document_length(1), 5);
+    }
+
+    #[test]
+    fn find_iter_streams_postings() {
+        let index = Index::new(&CORPUS);
+
+        assert_eq!(
+            index.find_iter("the").take(2).collect::<Vec<_>>(),
+            vec![2, 8]
+        );
+        assert_eq!(index.find_iter("the").collect::<Vec<_>>(), vec![2, 8, 9]);
+        assert_eq!(index.find_iter("moon").count(), 0);
+    }
+
+    #[test]
+    fn exists_matches_intersection() {
+        let index = Index::new(&CORPUS);
+
+        let intersects = |words: &[String]| {
+            let mut docs: HashSet<usize> = (0..CORPUS.len()).collect();
+            for word in words {
+                let found: HashSet<usize> = index.find_iter(word).collect();
+                docs.retain(|doc| found.contains(doc));
+            }
+            !docs.is_empty()
+        };
+
+        let queries: [&[&str]; 6] = [
+            &["the", "in"],
+            &["the", "sun."],
+            &["the", "Cats"],
+            &["in", "the", "night."],
+            &["moon", "the"],
+            &[],
+        ];
+        for query in queries {
+            let words: Vec<String> = query.iter().map(|word| word.to_string()).collect();
+            assert_eq!(index.exists(&words), intersects(&words), "{query:?}");
+        }
+
+        assert!(index.exists(&["the".to_string(), "in".to_string()]));
+        assert!(!index.exists(&["the".to_string(), "Cats".to_string()]));
+        assert_eq!(index.count_docs("the"), 3);
+        assert_eq!(Index::new(&["a b a", "a"]).count_docs("a"), 2);
+    }
+
+    #[test]
+    fn short_tokens_skipped() {
+        let index = Index::with_min_token_len(&CORPUS, 3);
+
+        assert_eq!(index.find("in"), None);
+        assert_eq!(index.find("on"), None);
+        assert_eq!(index.find("the"), Some(vec![2, 8, 9]));
+        assert_eq!(index.find("Cats"), Some(vec![0]));
+        assert_eq!(index.document_length(0), 7);
+
+        let words = |words: &[&str]| {
+            words
+                .iter()
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert!(index.exists(&words(&["in", "the", "night."])));
+        assert!(!Index::new(&CORPUS).exists(&words(&["on", "the"])));
+        assert!(index.exists(&words(&["on", "the"])));
+    }
+
+    #[test]
+    fn punctuation_stripped() {
+        let index = Index::new(&CORPUS);
+
+        assert_eq!(index.find("often"), Some(vec![0]));
+        assert_eq!(index.find("dawn"), Some(vec![4]));
+        assert_eq!(index.find("often,"), Some(vec![0]));
+        assert_eq!(index.find("sun."), Some(vec![8]));
+        assert_eq!(index.find("dawn's"), Some(vec![4]));
+        assert_eq!(index.count_docs("dawn's"), 1);
+        assert!(index.exists(&["dawn's".to_string(), "break".to_string()]));
+        assert_eq!(index.find("dawn-s"), Some(vec![4]));
+        assert_eq!(index.find("s dawn"), None);
+    }
+
+    #[test]
+    fn borrowed_runtime_corpus() {
+        let index = {
+            let lines: Vec<String> = CORPUS.iter().map(|line| line.to_string()).collect();
+            let corpus: Vec<&str> = lines.iter().map(String::as_str).collect();
+            Index::new(&corpus)
+        };
+
+        assert_eq!(index.find("the"), Some(vec![2, 8, 9]));
+    }
+
+    #[test]
+    fn find_within_filter() {
+        let index = Index::new(&CORPUS);
+
+        let allowed = HashSet::from([0, 2, 3, 9]);
+        assert_eq!(index.find_within("the", &allowed), vec![2, 9]);
+
+        let allowed = HashSet::from([8]);
+        assert_eq!(index.find_within("the", &allowed), vec![8]);
+
+        assert!(index.find_within("the", &HashSet::new()).is_empty());
+        assert!(index.find_within("moon", &allowed).is_empty());
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let sequential = Index::new(&CORPUS);
+        let parallel = Index::new_parallel(&CORPUS);
+        assert_eq!(parallel.inner, sequential.inner);
+        assert_eq!(parallel.lengths, sequential.lengths);
+
+        let docs: Vec<String> = (0..1000)
+            .map(|i| format!("doc{i} group{} common", i % 7))
+            .collect();
+        let corpus: Vec<&str> = docs.iter().map(String::as_str).collect();
+        let parallel = Index::new_parallel(&corpus);
+        assert_eq!(parallel.inner, Index::from_iter(docs.clone()).inner);
+        assert_eq!(parallel.find("common"), Some((0..1000).collect()));
+        assert_eq!(parallel.find("doc999"), Some(vec![999]));
+    }
+
+    #[test]
+    fn clear_and_rebuild() {
+        let mut index = Index::new(&CORPUS);
+        assert!(!index.is_empty());
+
+        index.clear();
+        assert!(index.is_empty());
+        assert_eq!(index.find("the"), None);
+        assert_eq!(index.average_document_length(), 0.0);
+
+        let capacity = index.inner.capacity();
+        index.extend(CORPUS);
+        assert!(!index.is_empty());
+        assert_eq!(index.inner.capacity(), capacity);
+        assert_eq!(index.find("the"), Some(vec![2, 8, 9]));
+        assert_eq!(index.document_length(9), 7);
+    }
+
+    #[test]
+    fn case_insensitive_lookup() {
+        let index = Index::new(&CORPUS);
+        assert_eq!(index.find("cats"), None);
+
+        let index = Index::new_case_insensitive(&CORPUS);
+        assert_eq!(index.find("cats"), Some(vec![0]));
+        assert_eq!(index.find("CATS"), Some(vec![0]));
+        assert_eq!(index.find("Cats"), Some(vec![0]));
+        assert_eq!(index.find("The"), Some(vec![2, 8, 9]));
+        assert_eq!(index.count_docs("STARS"), 1);
+        assert!(index.exists(&["Stars".to_string(), "NIGHT".to_string()]));
+    }
+
+    #[test]
+    fn remove_keeps_ids_stable() {
+        let mut index = Index::new(&CORPUS);
+
+        index.remove(2);
+        assert_eq!(index.find("the"), Some(vec![8, 9]));
+        assert_eq!(index.find("in"), Some(vec![0, 7]));
+        assert_eq!(index.find("Stars"), None);
+        assert!(!index.inner.contains_key("twinkle"));
+
+        // the remaining documents keep their ids
+        assert_eq!(index.find("Cats"), Some(vec![0]));
+        assert_eq!(index.find("sky"), Some(vec![9]));
+
+        index.extend(["the end"]);
+        assert_eq!(index.find("the"), Some(vec![8, 9, 10]));
+
+        index.remove(42);
+        assert_eq!(index.find("the"), Some(vec![8, 9, 10]));
+    }
+
+    #[test]
+    fn remove_updates_stats() {
+        let mut index = Index::new(&["a b c d", "a b", "a b c"]);
+        assert_eq!(index.average_document_length(), 3.0);
+
+        index.remove(0);
+        assert_eq!(index.average_document_length(), 2.5);
+        assert_eq!(index.document_length(0), 0);
+
+        // removing twice does not count the document twice
+        index.remove(0);
+        assert_eq!(index.average_document_length(), 2.5);
+
+        index.remove(1);
+        index.remove(2);
+        assert!(index.is_empty());
+        assert_eq!(index.average_document_length(), 0.0);
+        assert!(!index.exists(&[]));
+
+        index.extend(["x y"]);
+        assert!(!index.is_empty());
+        assert_eq!(index.average_document_length(), 2.0);
+    }
+
+    #[test]
+    fn phrase_requires_consecutive_words() {
+        let index = Index::new(&CORPUS);
+
+        assert_eq!(index.find_phrase("the night"), vec![2]);
+        assert_eq!(index.find_phrase("in the"), vec![2]);
+        assert_eq!(index.find_phrase("the sun."), vec![8]);
+        assert_eq!(index.find_phrase("Stars twinkle brightly"), vec![2]);
+
+        // both words occur in document 2, but in the other order
+        assert!(index.find_phrase("night the").is_empty());
+        assert!(index.find_phrase("the in").is_empty());
+        assert!(index.find_phrase("the moon").is_empty());
+        assert!(index.find_phrase("").is_empty());
+
+        // skipped short words still take up a position
+        let index = Index::with_min_token_len(&CORPUS, 3);
+        assert_eq!(index.find_phrase("basking in warm"), vec![0]);
+        assert_eq!(index.find_phrase("basking on warm"), vec![0]);
+        assert!(index.find_phrase("basking warm").is_empty());
+    }
+
+    #[test]
+    fn phrase_tolerates_gaps() {
+        let index = Index::new(&["the bright sun", "the sun", "sun the", "the big bright sun"]);
+
+        assert_eq!(index.find_phrase_tolerant(&["the", "sun"], 0), vec![1]);
+        assert_eq!(index.find_phrase_tolerant(&["the", "sun"], 1), vec![0, 1]);
+        assert_eq!(
+            index.find_phrase_tolerant(&["the", "sun"], 2),
+            vec![0, 1, 3]
+        );
+        assert_eq!(
+            index.find_phrase_tolerant(&["the", "bright", "sun"], 1),
+            vec![0, 3]
+        );
+        // the words must keep their order
+        assert!(index.find_phrase_tolerant(&["sun", "bright"], 5).is_empty());
+        assert!(index.find_phrase_tolerant(&["the", "moon"], 5).is_empty());
+        assert!(index.find_phrase_tolerant(&[], 5).is_empty());
+
+        // a later occurrence of the first word can close the gap
+        let index = Index::new(&["the a b the sun"]);
+        assert_eq!(index.find_phrase_tolerant(&["the", "sun"], 0), vec![0]);
+
+        let index = Index::new(&CORPUS);
+        assert_eq!(index.find_phrase_tolerant(&["the", "sun"], 0), vec![8]);
+        assert_eq!(index.find_phrase_tolerant(&["Stars", "night"], 4), vec![2]);
+        assert!(index
+            .find_phrase_tolerant(&["Stars", "night"], 3)
+            .is_empty());
+    }
+
+    #[test]
+    fn boolean_queries() {
+        let index = Index::new(&CORPUS);
+
+        assert_eq!(index.query_and(&["in", "the"]), vec![2]);
+        assert_eq!(index.query_and(&["the", "in"]), vec![2]);
+        assert_eq!(index.query_and(&["in", "the", "night"]), vec![2]);
+        assert!(index.query_and(&["in", "the", "moon"]).is_empty());
+        assert_eq!(index.query_and(&["the"]), vec![2, 8, 9]);
+        assert!(index.query_and(&[]).is_empty());
+
+        assert_eq!(index.query_or(&["in", "the"]), vec![0, 2, 7, 8, 9]);
+        assert_eq!(index.query_or(&["on", "moon"]), vec![1]);
+        assert!(index.query_or(&[]).is_empty());
+
+        assert_eq!(
+            index.query_not("the", CORPUS.len()),
+            vec![0, 1, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(index.query_not("moon", 3), vec![0, 1, 2]);
+
+        // repeats of a document in the postings appear once
+        let index = Index::new(&["a b a", "b", "a"]);
+        assert_eq!(index.query_and(&["a", "b"]), vec![0]);
+        assert_eq!(index.query_or(&["a", "b"]), vec![0, 1, 2]);
+        assert_eq!(index.query_not("a", 3), vec![1]);
+    }
+
+    #[test]
+    fn merge_halves() {
+        let (first, second) = CORPUS.split_at(CORPUS.len() / 2);
+        let mut merged = Index::new(first);
+        merged.merge(Index::new(second), first.len());
+
+        let combined = Index::new(&CORPUS);
+        assert_eq!(merged.inner, combined.inner);
+        assert_eq!(merged.lengths, combined.lengths);
+        assert_eq!(merged.find("the"), Some(vec![2, 8, 9]));
+        assert_eq!(merged.find("in"), Some(vec![0, 2, 7]));
+        assert_eq!(merged.find_phrase("the sun"), vec![8]);
+
+        // skipped ids are empty documents
+        let mut merged = Index::new(&["the cat"]);
+        merged.merge(Index::new(&["the dog"]), 3);
+        assert_eq!(merged.find("the"), Some(vec![0, 3]));
+        assert_eq!(merged.document_length(2), 0);
+
+        // removed documents stay removed
+        let mut other = Index::new(&["a b", "a b c d"]);
+        other.remove(1);
+        let mut merged = Index::new(&["a b c d"]);
+        merged.merge(other, 1);
+        assert_eq!(merged.average_document_length(), 3.0);
+        merged.remove(2);
+        assert_eq!(merged.average_document_length(), 3.0);
+    }
+
+    #[test]
+    fn merge_interleaved_ids() {
+        // the even documents in one index and the odd ones in another
+        let evens: Vec<&str> = CORPUS.iter().step_by(2).copied().collect();
+        let odds: Vec<&str> = CORPUS.iter().skip(1).step_by(2).copied().collect();
+        let spread = |docs: &[&str], first: usize| {
+            let mut index = Index::new(&[]);
+            for (i, &doc) in docs.iter().enumerate() {
+                index.merge(Index::new(&[doc]), first + 2 * i);
+            }
+            index
+        };
+
+        let mut merged = spread(&evens, 0);
+        merged.merge(spread(&odds, 1), 0);
+
+        let combined = Index::new(&CORPUS);
+        assert_eq!(merged.inner, combined.inner);
+        assert_eq!(merged.lengths, combined.lengths);
+        assert_eq!(merged.find("in"), Some(vec![0, 2, 7]));
+        assert_eq!(merged.find_phrase("the sun"), vec![8]);
+
+        // a merged document fills a removed id
+        let mut index = Index::new(&["the cat", "the dog"]);
+        index.remove(0);
+        index.merge(Index::new(&["the cow"]), 0);
+        assert_eq!(index.find("the"), Some(vec![0, 1]));
+        assert_eq!(index.find("cow"), Some(vec![0]));
+        assert_eq!(index.average_document_length(), 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "same tokenizing options")]
+    fn merge_rejects_mismatched_options() {
+        let mut index = Index::new_case_insensitive(&CORPUS);
+        index.merge(Index::new(&["The End"]), CORPUS.len());
+    }
 }
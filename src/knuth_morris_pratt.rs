@@ -0,0 +1,1312 @@
+use std::{
+    cmp::max,
+    collections::{HashMap, HashSet, VecDeque},
+    io::{self, ErrorKind, Read},
+    str::Chars,
+};
+
+use crate::{naive, pattern::CompiledPattern, profile::Counters};
+
+/// Knuth-Morris-Pratt string search achieves linear time complexity by
+/// preprocessing the pattern to determine how much of the pattern to
+/// reevalaute once a mismatch is found. The text cursor only moves forward,
+/// meaning each text character is only evaluated once.
+///
+/// The partial match table specifies the amount to backtrack the pattern
+/// cursor. If the backtrack value is -1, we do not backtrack at all but
+/// instead advance both cursors. If the backtrack value is positive, set
+/// the pattern cursor to the backtrack value. The Wikipedia page for the
+/// algorithm has a useful reference implementation:
+/// https://en.wikipedia.org/wiki/Knuth%E2%80%93Morris%E2%80%93Pratt_algorithm.
+pub fn contains(pattern: &str, text: &str) -> bool {
+    Kmp::new(pattern).contains(text)
+}
+
+/// A pattern with its partial match table computed up front, so that it can
+/// be searched for in many texts without rebuilding the table.
+pub struct Kmp {
+    pattern: Vec<char>,
+    partial_match_table: Vec<isize>,
+}
+
+impl Kmp {
+    pub fn new(pattern: &str) -> Self {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let partial_match_table = partial_match_table(&pattern);
+        Self {
+            pattern,
+            partial_match_table,
+        }
+    }
+
+    /// Checks whether the pattern occurs in the text.
+    pub fn contains(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        search(
+            &self.pattern,
+            &self.partial_match_table,
+            &text,
+            &mut Counters::default(),
+        )
+    }
+}
+
+/// Same as [`contains`], but ignores case, with the same folding and
+/// limitations as [`naive::contains_ignore_case`]. The pattern is folded once
+/// up front and each text char is folded as it is read.
+pub fn contains_ignore_case(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(naive::fold_case).collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    let border_table = border_table(&pattern);
+
+    let mut j = 0;
+    for ch in text.chars().map(naive::fold_case) {
+        while j > 0 && ch != pattern[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == pattern[j] {
+            j += 1;
+        }
+
+        if j == pattern.len() {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Same as [`contains`], but treats the chars within each group as equal,
+/// e.g. `'0'` and `'O'` for text recognized from scans. Every char is
+/// replaced by the first char of its group before searching, so the partial
+/// match table is built under the same equivalence as the comparisons. A
+/// char listed in more than one group belongs to the first of them.
+pub fn contains_with_equivalences(pattern: &str, text: &str, groups: &[&[char]]) -> bool {
+    let mut canonical = HashMap::new();
+    for group in groups {
+        if let Some(&first) = group.first() {
+            for &ch in group.iter() {
+                canonical.entry(ch).or_insert(first);
+            }
+        }
+    }
+
+    let canonicalize = |s: &str| -> Vec<char> {
+        s.chars()
+            .map(|ch| canonical.get(&ch).copied().unwrap_or(ch))
+            .collect()
+    };
+    contains_slice(&canonicalize(pattern), &canonicalize(text))
+}
+
+/// Same as [`contains`], but searches a sequence of any comparable items,
+/// such as bytes or tokens, instead of the chars of a string.
+pub fn contains_slice<T: PartialEq>(pattern: &[T], text: &[T]) -> bool {
+    contains_counted(pattern, text, &mut Counters::default())
+}
+
+/// Same as [`contains`], but reuses the given buffers as described in
+/// [`naive::contains_with_buffers`].
+pub fn contains_with_buffers(
+    pattern: &str,
+    text: &str,
+    pattern_buf: &mut Vec<char>,
+    text_buf: &mut Vec<char>,
+) -> bool {
+    pattern_buf.clear();
+    pattern_buf.extend(pattern.chars());
+    text_buf.clear();
+    text_buf.extend(text.chars());
+    let (pattern, text) = (&pattern_buf[..], &text_buf[..]);
+
+    contains_counted(pattern, text, &mut Counters::default())
+}
+
+/// Runs the search over the collected items, recording the work done in
+/// `counters`.
+pub(crate) fn contains_counted<T: PartialEq>(
+    pattern: &[T],
+    text: &[T],
+    counters: &mut Counters,
+) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+
+    if text.is_empty() || text.len() < pattern.len() {
+        return false;
+    }
+
+    search(pattern, &partial_match_table(pattern), text, counters)
+}
+
+/// Runs the search with a partial match table computed beforehand.
+fn search<T: PartialEq>(
+    pattern: &[T],
+    partial_match_table: &[isize],
+    text: &[T],
+    counters: &mut Counters,
+) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+
+    let mut i = 0;
+    let mut j = 0;
+    while i < text.len() {
+        counters.comparisons += 1;
+        if text[i] == pattern[j] {
+            i += 1;
+            j += 1;
+
+            if j == pattern.len() {
+                return true;
+            }
+        } else {
+            counters.shifts += 1;
+            let k = partial_match_table[j];
+            if k < 0 {
+                i += 1;
+                j = (k + 1) as usize;
+            } else {
+                j = k as usize;
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns the char index of every match, including matches that overlap,
+/// collected from [`matches()`]. After a match the pattern cursor falls back
+/// to the longest border of the pattern, as in [`count_overlapping`]. An
+/// empty pattern matches at every char boundary.
+pub fn find_all(pattern: &str, text: &str) -> Vec<usize> {
+    matches(pattern, text).collect()
+}
+
+/// Same as [`find_all`], but yields the matches lazily as the scan reaches
+/// them instead of collecting them up front. The text is only read as far as
+/// the last match requested.
+pub fn matches<'a>(pattern: &str, text: &'a str) -> Matches<'a> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let border_table = border_table(&pattern);
+    Matches {
+        pattern,
+        border_table,
+        text: text.chars(),
+        i: 0,
+        j: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the char index of every match, returned by [`matches()`].
+/// The pattern cursor is kept between calls to `next`, so the scan resumes
+/// exactly where it stopped.
+pub struct Matches<'a> {
+    pattern: Vec<char>,
+    border_table: Vec<usize>,
+    /// The chars not scanned yet.
+    text: Chars<'a>,
+    /// The number of chars scanned so far.
+    i: usize,
+    j: usize,
+    /// Whether an empty pattern has matched at the end of the text.
+    done: bool,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.pattern.is_empty() {
+            if self.done {
+                return None;
+            }
+
+            let at = self.i;
+            match self.text.next() {
+                Some(_) => self.i += 1,
+                None => self.done = true,
+            }
+            return Some(at);
+        }
+
+        for ch in self.text.by_ref() {
+            self.i += 1;
+            while self.j > 0 && ch != self.pattern[self.j] {
+                self.j = self.border_table[self.j - 1];
+            }
+
+            if ch == self.pattern[self.j] {
+                self.j += 1;
+            }
+
+            if self.j == self.pattern.len() {
+                self.j = self.border_table[self.j - 1];
+                return Some(self.i - self.pattern.len());
+            }
+        }
+
+        None
+    }
+}
+
+/// Counts every occurrence of the pattern, including occurrences that
+/// overlap one another, so `"aa"` occurs 3 times in `"aaaa"`. After a
+/// match the pattern cursor falls back to the longest border of the
+/// pattern instead of restarting. An empty pattern occurs at every char
+/// boundary.
+///
+/// See [`count_non_overlapping`] for counting occurrences the way
+/// `str::matches` does.
+pub fn count_overlapping(pattern: &str, text: &str) -> usize {
+    count(pattern, text, true)
+}
+
+/// Counts the occurrences of the pattern that do not overlap, scanning
+/// left to right and resuming after the end of each match, so `"aa"`
+/// occurs 2 times in `"aaaa"`. An empty pattern occurs at every char
+/// boundary.
+///
+/// See [`count_overlapping`] for counting every occurrence.
+pub fn count_non_overlapping(pattern: &str, text: &str) -> usize {
+    count(pattern, text, false)
+}
+
+/// Counts the occurrences of the pattern, either all of them as in
+/// [`count_overlapping`] or only those that do not overlap as in
+/// [`count_non_overlapping`]. Without overlaps the pattern cursor restarts
+/// after the end of each match instead of falling back to a border.
+pub fn count(pattern: &str, text: &str, overlapping: bool) -> usize {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if pattern.is_empty() {
+        return text.chars().count() + 1;
+    }
+
+    let border_table = border_table(&pattern);
+
+    let mut count = 0;
+    let mut j = 0;
+    for ch in text.chars() {
+        while j > 0 && ch != pattern[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == pattern[j] {
+            j += 1;
+        }
+
+        if j == pattern.len() {
+            count += 1;
+            j = if overlapping { border_table[j - 1] } else { 0 };
+        }
+    }
+
+    count
+}
+
+/// Returns the char range of every match, including matches that overlap,
+/// so `"aaa"` occurs at `0..3`, `1..4` and `2..5` in `"aaaaa"`. These are the
+/// ranges of [`find_all`]. In contrast, [`find_all_byte_ranges`] resumes
+/// after the end of each match, so it finds only `0..3` there.
+///
+/// Self-overlapping patterns are where this goes wrong most easily: after a
+/// match the pattern cursor must fall back to the longest border of the
+/// pattern, not to 0, or the matches that start inside the previous one are
+/// missed.
+pub fn find_all_overlapping(pattern: &str, text: &str) -> Vec<(usize, usize)> {
+    let len = pattern.chars().count();
+    find_all(pattern, text)
+        .into_iter()
+        .map(|start| (start, start + len))
+        .collect()
+}
+
+/// The most ranges [`find_all_byte_ranges`] reserves room for before it
+/// starts searching.
+const RANGES_ESTIMATE_CAP: usize = 1024;
+
+/// Returns the byte ranges of the non-overlapping matches, scanning left
+/// to right like [`count_non_overlapping`], so each range can be used to
+/// slice the match out of the text. The result is sized up front from the
+/// most matches that could fit in the text, rather than by counting the
+/// matches in a separate pass, and the estimate is capped so that short
+/// patterns do not reserve far more than they use. An empty pattern matches
+/// at every char boundary.
+pub fn find_all_byte_ranges(pattern: &str, text: &str) -> Vec<(usize, usize)> {
+    if pattern.is_empty() {
+        let mut ranges: Vec<(usize, usize)> = text.char_indices().map(|(i, _)| (i, i)).collect();
+        ranges.push((text.len(), text.len()));
+        return ranges;
+    }
+
+    let mut ranges = Vec::with_capacity((text.len() / pattern.len()).min(RANGES_ESTIMATE_CAP));
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let border_table = border_table(&chars);
+
+    let mut j = 0;
+    for (i, ch) in text.char_indices() {
+        while j > 0 && ch != chars[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == chars[j] {
+            j += 1;
+        }
+
+        if j == chars.len() {
+            // the matched text is the pattern, so it spans as many bytes
+            let end = i + ch.len_utf8();
+            ranges.push((end - pattern.len(), end));
+            j = 0;
+        }
+    }
+
+    ranges
+}
+
+/// Returns a copy of the text with every non-overlapping match replaced,
+/// scanning left to right as in [`find_all_byte_ranges`], so replacing
+/// `"aa"` in `"aaa"` gives one replacement followed by the last `"a"`. An
+/// empty pattern leaves the text unchanged.
+pub fn replace_all(pattern: &str, replacement: &str, text: &str) -> String {
+    if pattern.is_empty() {
+        return text.to_string();
+    }
+
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in find_all_byte_ranges(pattern, text) {
+        replaced.push_str(&text[last..start]);
+        replaced.push_str(replacement);
+        last = end;
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+/// Splits the text at every non-overlapping match, like `str::split` with a
+/// string pattern, collecting the segments from
+/// [`CompiledPattern::split_on`](crate::pattern::CompiledPattern::split_on).
+/// Matches at either end or next to each other produce empty segments. As
+/// there, an empty pattern never matches, so the text comes back whole.
+pub fn split_on(pattern: &str, text: &str) -> Vec<String> {
+    CompiledPattern::new(pattern)
+        .split_on(text)
+        .map(String::from)
+        .collect()
+}
+
+/// Returns the length (in chars) of the longest prefix of the pattern that
+/// occurs somewhere in the text, e.g. 4 for `"abcdef"` in `"xxabcdyy"`. A
+/// full match returns the pattern length.
+///
+/// After each text char, the pattern cursor sits at the longest prefix of
+/// the pattern that ends at that char, so the answer is the furthest the
+/// cursor ever reaches during a single scan.
+pub fn longest_prefix_match(pattern: &str, text: &str) -> usize {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if pattern.is_empty() {
+        return 0;
+    }
+
+    let border_table = border_table(&pattern);
+
+    let mut longest = 0;
+    let mut j = 0;
+    for ch in text.chars() {
+        while j > 0 && ch != pattern[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == pattern[j] {
+            j += 1;
+        }
+
+        longest = max(longest, j);
+        if longest == pattern.len() {
+            break;
+        }
+    }
+
+    longest
+}
+
+/// Returns the lengths of all borders of the pattern (proper prefixes that
+/// are also suffixes), longest first, e.g. `[2, 1]` for `"aabaa"`. Every
+/// border of a border is itself a border, so the list is found by
+/// following the border table from the end of the pattern.
+pub fn borders(pattern: &str) -> Vec<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let border_table = border_table(&pattern);
+
+    let mut borders = Vec::new();
+    let mut len = border_table.last().copied().unwrap_or(0);
+    while len > 0 {
+        borders.push(len);
+        len = border_table[len - 1];
+    }
+    borders
+}
+
+/// Returns the shortest period of the pattern, the smallest shift that
+/// lines the pattern up with itself, e.g. 3 for `"abcabcab"`. This is the
+/// pattern length minus its longest border.
+pub fn shortest_period(pattern: &str) -> usize {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let border_table = border_table(&pattern);
+    pattern.len() - border_table.last().copied().unwrap_or(0)
+}
+
+/// Same as [`contains`], but when `across_lines` is false a match must lie
+/// within a single line, as in grep. A newline then resets the pattern
+/// cursor, so no match can contain or straddle one.
+pub fn contains_multiline(pattern: &str, text: &str, across_lines: bool) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    let border_table = border_table(&pattern);
+
+    let mut j = 0;
+    for ch in text.chars() {
+        if ch == '\n' && !across_lines {
+            j = 0;
+            continue;
+        }
+
+        while j > 0 && ch != pattern[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == pattern[j] {
+            j += 1;
+        }
+
+        if j == pattern.len() {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Searches for one pattern in many texts, returning the char index of the
+/// first match in each text. The pattern is preprocessed once, and texts
+/// that lack any of the pattern's chars are rejected by a presence check
+/// before running the full search. The check reads each text once, stopping
+/// as soon as every pattern char has been seen, and needs no border table
+/// lookups, so it pays off when most texts do not contain the pattern.
+pub fn search_many(pattern: &str, texts: &[&str]) -> Vec<Option<usize>> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let border_table = border_table(&pattern);
+    let required: HashSet<char> = pattern.iter().copied().collect();
+
+    texts
+        .iter()
+        .map(|text| {
+            if !has_all_chars(&required, text) {
+                return None;
+            }
+            find(&pattern, &border_table, text)
+        })
+        .collect()
+}
+
+/// Checks whether every char in the set occurs somewhere in the text, in a
+/// single scan.
+fn has_all_chars(required: &HashSet<char>, text: &str) -> bool {
+    if required.is_empty() {
+        return true;
+    }
+
+    let mut seen = HashSet::with_capacity(required.len());
+    for ch in text.chars() {
+        if required.contains(&ch) && seen.insert(ch) && seen.len() == required.len() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Checks whether all of the parts occur in the text in the given order
+/// without overlapping, e.g. `"error"` followed later by `"timeout"`. Each
+/// part is searched for from the end of the previous part's first match,
+/// which is the earliest place any later part could start.
+pub fn contains_ordered(parts: &[&str], text: &str) -> bool {
+    let mut rest = text;
+    for part in parts {
+        let pattern: Vec<char> = part.chars().collect();
+        let border_table = border_table(&pattern);
+
+        let end = match find(&pattern, &border_table, rest) {
+            Some(start) => start + pattern.len(),
+            None => return false,
+        };
+        let offset = rest.char_indices().nth(end).map_or(rest.len(), |(i, _)| i);
+        rest = &rest[offset..];
+    }
+    true
+}
+
+/// Returns the char index of the first match, using a border table computed
+/// beforehand. An empty pattern matches at 0.
+pub(crate) fn find(pattern: &[char], border_table: &[usize], text: &str) -> Option<usize> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut j = 0;
+    for (i, ch) in text.chars().enumerate() {
+        while j > 0 && ch != pattern[j] {
+            j = border_table[j - 1];
+        }
+
+        if ch == pattern[j] {
+            j += 1;
+        }
+
+        if j == pattern.len() {
+            return Some(i + 1 - pattern.len());
+        }
+    }
+
+    None
+}
+
+/// The number of bytes read from the stream at a time by [`find_in_reader`].
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// Returns the byte offset of the first match in a stream, such as a file or
+/// socket, without loading it into memory. The pattern is matched byte by
+/// byte as the stream is read through a fixed-size buffer; because the text
+/// cursor never moves backwards, a match that spans two reads needs no bytes
+/// from the earlier read to be kept. An empty pattern matches at 0.
+pub fn find_in_reader<R: Read>(pattern: &str, mut reader: R) -> io::Result<Option<u64>> {
+    let pattern = pattern.as_bytes();
+
+    if pattern.is_empty() {
+        return Ok(Some(0));
+    }
+
+    let border_table = border_table(pattern);
+
+    let mut buffer = [0; READ_BUFFER_SIZE];
+    let mut offset: u64 = 0;
+    let mut j = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(read) => read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        for (i, &byte) in buffer[..read].iter().enumerate() {
+            while j > 0 && byte != pattern[j] {
+                j = border_table[j - 1];
+            }
+
+            if byte == pattern[j] {
+                j += 1;
+            }
+
+            if j == pattern.len() {
+                let end = offset + i as u64 + 1;
+                return Ok(Some(end - pattern.len() as u64));
+            }
+        }
+
+        offset += read as u64;
+    }
+}
+
+/// Searches a stream one char at a time, reporting only matches whose chars
+/// all arrived within `window_size` of each other. Each char carries a
+/// position in the stream, such as a timestamp, given to
+/// [`WindowSearcher::push_at`]. A partial match whose first char has fallen
+/// out of the window is dropped by falling back to a shorter border, which
+/// may still complete in time.
+///
+/// With [`WindowSearcher::push`] the chars take consecutive positions, so a
+/// match always spans `pattern.len() - 1` and the window only decides whether
+/// the pattern fits in it at all.
+pub struct WindowSearcher {
+    pattern: Vec<char>,
+    border_table: Vec<usize>,
+    window_size: u64,
+    /// The positions of the most recent chars, up to the pattern length.
+    positions: VecDeque<u64>,
+    /// The position [`WindowSearcher::push`] gives to the next char.
+    next_position: u64,
+    j: usize,
+}
+
+impl WindowSearcher {
+    pub fn new(pattern: &str, window_size: usize) -> Self {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let border_table = border_table(&pattern);
+        Self {
+            positions: VecDeque::with_capacity(pattern.len()),
+            pattern,
+            border_table,
+            window_size: window_size as u64,
+            next_position: 0,
+            j: 0,
+        }
+    }
+
+    /// Feeds the next char of the stream at the position after the previous
+    /// one, returning whether a match ends at it. Matches may overlap.
+    pub fn push(&mut self, ch: char) -> bool {
+        self.push_at(ch, self.next_position)
+    }
+
+    /// Feeds the next char of the stream at `position`, returning whether a
+    /// match ends at it. A match counts only if the positions of its first
+    /// and last chars are less than `window_size` apart. Positions must not
+    /// decrease.
+    pub fn push_at(&mut self, ch: char, position: u64) -> bool {
+        debug_assert!(
+            self.positions.back().is_none_or(|&last| last <= position),
+            "positions must not decrease"
+        );
+        self.next_position = position + 1;
+
+        if self.pattern.is_empty() {
+            return false;
+        }
+
+        if self.positions.len() == self.pattern.len() {
+            self.positions.pop_front();
+        }
+        self.positions.push_back(position);
+
+        while self.j > 0 && ch != self.pattern[self.j] {
+            self.j = self.border_table[self.j - 1];
+        }
+
+        if ch == self.pattern[self.j] {
+            self.j += 1;
+        }
+
+        // drop the oldest chars of the partial match until it fits the window
+        while self.j > 0
+            && position - self.positions[self.positions.len() - self.j] >= self.window_size
+        {
+            self.j = self.border_table[self.j - 1];
+        }
+
+        if self.j == self.pattern.len() {
+            self.j = self.border_table[self.j - 1];
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Searches for a pattern in which each position accepts any char from a
+/// set (a character class), such as the IUPAC nucleotide codes. An empty
+/// pattern matches any text.
+///
+/// The border table is generalized so that two positions are considered
+/// equal when their sets overlap. A shift shorter than the one given by
+/// the table is then impossible, since some text char would have to lie
+/// in two disjoint sets. However, knowing that the text matched the
+/// pattern before the shift does not guarantee that it matches the border
+/// after it, so the border is checked again rather than skipped. This
+/// makes the worst case O(mn) unless all of the sets are disjoint or
+/// singletons.
+pub fn contains_classes(pattern: &[HashSet<char>], text: &str) -> bool {
+    search_classes(
+        pattern,
+        text,
+        |set, ch| set.contains(&ch),
+        |a, b| !a.is_disjoint(b),
+    )
+}
+
+/// A single position of a class pattern.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Class {
+    /// Accepts any of the chars, as in `[abc]` or a plain `a`.
+    In(HashSet<char>),
+    /// Accepts any char except these, as in `[^abc]`.
+    NotIn(HashSet<char>),
+}
+
+impl Class {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Class::In(set) => set.contains(&ch),
+            Class::NotIn(set) => !set.contains(&ch),
+        }
+    }
+
+    /// Returns whether some char is accepted by both classes. Two negated
+    /// classes always overlap, since each excludes only finitely many chars.
+    fn overlaps(&self, other: &Class) -> bool {
+        match (self, other) {
+            (Class::In(a), Class::In(b)) => !a.is_disjoint(b),
+            (Class::In(a), Class::NotIn(b)) | (Class::NotIn(b), Class::In(a)) => !a.is_subset(b),
+            (Class::NotIn(_), Class::NotIn(_)) => true,
+        }
+    }
+}
+
+/// Parses a pattern made of literal chars and bracketed classes, such as
+/// `"a[bc]d"` or `"a[^xy]c"`. A `^` right after the opening bracket negates
+/// the class. A bracket that is never closed is taken literally.
+pub fn parse_classes(pattern: &str) -> Vec<Class> {
+    let chars: Vec<char> = pattern.chars().collect();
+
+    let mut classes = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let close = match chars[i] {
+            '[' => chars[i + 1..].iter().position(|&ch| ch == ']'),
+            _ => None,
+        };
+
+        match close {
+            Some(offset) => {
+                let body = &chars[i + 1..i + 1 + offset];
+                classes.push(match body.split_first() {
+                    Some(('^', rest)) => Class::NotIn(rest.iter().copied().collect()),
+                    _ => Class::In(body.iter().copied().collect()),
+                });
+                i += offset + 2;
+            }
+            None => {
+                classes.push(Class::In(HashSet::from([chars[i]])));
+                i += 1;
+            }
+        }
+    }
+    classes
+}
+
+/// Same as [`contains_classes`], but the pattern is written with
+/// [`parse_classes`] syntax, so classes may also be negated.
+pub fn contains_class_pattern(pattern: &str, text: &str) -> bool {
+    search_classes(
+        &parse_classes(pattern),
+        text,
+        Class::matches,
+        Class::overlaps,
+    )
+}
+
+fn search_classes<C>(
+    pattern: &[C],
+    text: &str,
+    matches: impl Fn(&C, char) -> bool,
+    overlaps: impl Fn(&C, &C) -> bool,
+) -> bool {
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    let table = class_border_table(pattern, overlaps);
+
+    let mut start = 0;
+    let mut j = 0;
+    while start + pattern.len() <= text.len() {
+        if matches(&pattern[j], text[start + j]) {
+            j += 1;
+
+            if j == pattern.len() {
+                return true;
+            }
+        } else {
+            start += if j == 0 { 1 } else { j - table[j] };
+            j = 0;
+        }
+    }
+
+    false
+}
+
+/// Returns, for each prefix length, the length of the longest proper
+/// border of that prefix in which all aligned classes overlap. The
+/// relation is not transitive, so each entry is computed directly rather
+/// than from the previous ones.
+fn class_border_table<C>(pattern: &[C], overlaps: impl Fn(&C, &C) -> bool) -> Vec<usize> {
+    let mut table = vec![0; pattern.len() + 1];
+    for len in 2..=pattern.len() {
+        table[len] = (1..len)
+            .rev()
+            .find(|&border| (0..border).all(|k| overlaps(&pattern[k], &pattern[len - border + k])))
+            .unwrap_or(0);
+    }
+    table
+}
+
+fn partial_match_table<T: PartialEq>(pattern: &[T]) -> Vec<isize> {
+    let mut table = vec![-1]; // no shift if there is no match
+    let mut cnd = 0;
+    for i in 1..pattern.len() {
+        if pattern[i] == pattern[cnd as usize] {
+            table.push(table[cnd as usize]);
+        } else {
+            table.push(cnd);
+            while cnd >= 0 && pattern[i] != pattern[cnd as usize] {
+                cnd = table[cnd as usize];
+            }
+        }
+        cnd += 1;
+    }
+    table
+}
+
+/// Returns, for each position, the length of the longest proper border
+/// (prefix that is also a suffix) of the pattern up to that position. This
+/// is the classic form of the partial match table, without the -1 entries
+/// that let [`contains`] skip known mismatches.
+pub(crate) fn border_table<T: PartialEq>(pattern: &[T]) -> Vec<usize> {
+    let mut table = vec![0; pattern.len()];
+    let mut cnd = 0;
+    for i in 1..pattern.len() {
+        while cnd > 0 && pattern[i] != pattern[cnd] {
+            cnd = table[cnd - 1];
+        }
+
+        if pattern[i] == pattern[cnd] {
+            cnd += 1;
+        }
+        table[i] = cnd;
+    }
+    table
+}
+
+#[test]
+fn matches_are_lazy() {
+    let text = format!("abab{}", "ab".repeat(100_000));
+    let mut found = matches("bab", &text);
+    assert_eq!(found.by_ref().take(2).collect::<Vec<_>>(), vec![1, 3]);
+
+    // only the first six chars were scanned
+    assert_eq!(found.text.as_str().len(), text.len() - 6);
+    assert_eq!(found.next(), Some(5));
+
+    for (pattern, text) in [("aa", "aaaa"), ("abc", "xabcabcx"), ("", "ab"), ("x", "")] {
+        assert_eq!(
+            matches(pattern, text).collect::<Vec<_>>(),
+            find_all(pattern, text),
+            "{pattern:?} in {text:?}"
+        );
+    }
+}
+
+#[test]
+fn compiled_pattern_reused() {
+    let matcher = Kmp::new("needle");
+    let texts: Vec<String> = (0..100)
+        .map(|i| match i % 10 {
+            0 => format!("haystack {i} with a needle in it"),
+            _ => format!("haystack {i} with a needl in it"),
+        })
+        .collect();
+
+    let found = texts.iter().filter(|text| matcher.contains(text)).count();
+    assert_eq!(found, 10);
+    for text in &texts {
+        assert_eq!(matcher.contains(text), text.contains("needle"), "{text}");
+    }
+
+    assert!(Kmp::new("").contains(""));
+    assert!(!Kmp::new("a").contains(""));
+}
+
+#[test]
+fn contains_ignore_case_folds_both_sides() {
+    assert!(contains_ignore_case("ABC", "abcdef"));
+    assert!(contains_ignore_case("aBc", "xxAbCxx"));
+    assert!(contains_ignore_case("ÉTÉ", "un été chaud"));
+    assert!(contains_ignore_case("AAB", "aAaAb"));
+    assert!(!contains_ignore_case("abd", "ABCDEF"));
+    assert!(!contains_ignore_case("STRASSE", "straße"));
+}
+
+#[test]
+fn equivalent_chars_match() {
+    let groups: &[&[char]] = &[&['0', 'O', 'o'], &['1', 'l', 'I']];
+
+    assert!(contains_with_equivalences("l0g", "log", groups));
+    assert!(contains_with_equivalences("log", "the 1Og file", groups));
+    assert!(!contains_with_equivalences("l0g", "lag", groups));
+    assert!(!contains("l0g", "log"));
+
+    // the table sees "0O" as a repeated char, so the overlap is not missed
+    assert!(contains_with_equivalences("0O1", "oo0O1", groups));
+    assert!(contains_with_equivalences("", "log", &[]));
+}
+
+#[test]
+fn contains_slice_compares_items() {
+    // the table is built over items just as it is over chars
+    assert_eq!(partial_match_table(b"abcdabd"), vec![-1, 0, 0, 0, -1, 0, 2]);
+    assert!(contains_slice(b"abcabd", b"abcabcabd"));
+    assert!(contains_slice(&[300u32, 300, 400], &[300, 300, 300, 400]));
+    assert!(!contains_slice(b"abab", b"abaab"));
+
+    let cases: [(&[u8], &[u8]); 4] = [
+        (b"aab", b"aaab"),
+        (b"abab", b"abaabab"),
+        (b"", b""),
+        (b"abc", b"ab"),
+    ];
+    for (pattern, text) in cases {
+        assert_eq!(
+            contains_slice(pattern, text),
+            naive::contains_slice(pattern, text),
+            "{pattern:?} in {text:?}"
+        );
+    }
+}
+
+#[test]
+fn partial_match_table_correct() {
+    let pattern: Vec<char> = "abcdabd".chars().collect();
+    let table = partial_match_table(&pattern);
+    assert_eq!(table, vec![-1, 0, 0, 0, -1, 0, 2]);
+}
+
+#[test]
+fn contains_classes_matches_any_member() {
+    let pattern = [HashSet::from(['A', 'C']), HashSet::from(['G', 'T'])];
+    for text in ["AG", "AT", "CG", "CT", "TTCTT"] {
+        assert!(contains_classes(&pattern, text), "{text}");
+    }
+    for text in ["AA", "GC", "TA", "AxG"] {
+        assert!(!contains_classes(&pattern, text), "{text}");
+    }
+}
+
+#[test]
+fn class_border_table_correct() {
+    let pattern = [
+        HashSet::from(['a']),
+        HashSet::from(['a', 'b']),
+        HashSet::from(['b']),
+    ];
+    assert_eq!(
+        class_border_table(&pattern, |a, b| !a.is_disjoint(b)),
+        vec![0, 0, 1, 2]
+    );
+    assert!(contains_classes(&pattern, "aaab"));
+}
+
+#[test]
+fn negated_classes() {
+    assert!(contains_class_pattern("a[^xy]c", "abc"));
+    assert!(contains_class_pattern("a[^xy]c", "xxaxcazc"));
+    assert!(!contains_class_pattern("a[^xy]c", "axc"));
+    assert!(!contains_class_pattern("a[^xy]c", "ayc"));
+
+    assert!(contains_class_pattern("[ab][^b]", "bbba"));
+    assert!(!contains_class_pattern("[ab][^ab]", "abba"));
+    assert!(contains_class_pattern("[", "a[b"));
+}
+
+#[test]
+fn parse_classes_syntax() {
+    assert_eq!(
+        parse_classes("a[^xy][bc"),
+        vec![
+            Class::In(HashSet::from(['a'])),
+            Class::NotIn(HashSet::from(['x', 'y'])),
+            Class::In(HashSet::from(['['])),
+            Class::In(HashSet::from(['b'])),
+            Class::In(HashSet::from(['c'])),
+        ]
+    );
+    assert_eq!(parse_classes("[]"), vec![Class::In(HashSet::new())]);
+}
+
+#[test]
+fn border_table_correct() {
+    let pattern: Vec<char> = "abcdabd".chars().collect();
+    assert_eq!(border_table(&pattern), vec![0, 0, 0, 0, 1, 2, 0]);
+}
+
+#[test]
+fn counts_with_and_without_overlap() {
+    assert_eq!(count_overlapping("aa", "aaaa"), 3);
+    assert_eq!(count_non_overlapping("aa", "aaaa"), 2);
+
+    assert_eq!(count_overlapping("aba", "ababa"), 2);
+    assert_eq!(count_non_overlapping("aba", "ababa"), 1);
+
+    assert_eq!(count_overlapping("abc", "abcxabc"), 2);
+    assert_eq!(count_non_overlapping("abc", "abcxabc"), 2);
+    assert_eq!(count_overlapping("abd", "abcxabc"), 0);
+
+    assert_eq!(count("aa", "aaaa", true), 3);
+    assert_eq!(count("aa", "aaaa", false), 2);
+    assert_eq!(count("abc", "abcabcab", true), 2);
+    assert_eq!(count("abc", "abcabcab", false), 2);
+}
+
+#[test]
+fn overlapping_ranges_of_self_overlapping_patterns() {
+    let starts = |pattern: &str, text: &str| -> Vec<usize> {
+        find_all_overlapping(pattern, text)
+            .into_iter()
+            .map(|(start, end)| {
+                assert_eq!(end - start, pattern.chars().count());
+                start
+            })
+            .collect()
+    };
+
+    assert_eq!(starts("aaa", "aaaaa"), vec![0, 1, 2]);
+    assert_eq!(starts("aba", "abababa"), vec![0, 2, 4]);
+    assert_eq!(starts("abab", "abababab"), vec![0, 2, 4]);
+    assert_eq!(starts("aabaa", "aabaabaa"), vec![0, 3]);
+    assert_eq!(
+        find_all_overlapping("aaa", "aaaaa"),
+        vec![(0, 3), (1, 4), (2, 5)]
+    );
+
+    // every pattern and text over {a, b} up to a small length, against a
+    // direct check at each position
+    let strings = |max_len: usize| -> Vec<String> {
+        let mut all = vec![String::new()];
+        let mut last = vec![String::new()];
+        for _ in 0..max_len {
+            last = last
+                .iter()
+                .flat_map(|s| [format!("{s}a"), format!("{s}b")])
+                .collect();
+            all.extend(last.iter().cloned());
+        }
+        all
+    };
+
+    let texts = strings(8);
+    for pattern in strings(4).iter().filter(|pattern| !pattern.is_empty()) {
+        for text in &texts {
+            let expected: Vec<usize> = (0..text.len())
+                .filter(|&i| text[i..].starts_with(pattern.as_str()))
+                .collect();
+            assert_eq!(starts(pattern, text), expected, "{pattern:?} in {text:?}");
+        }
+    }
+}
+
+#[test]
+fn replace_all_non_overlapping() {
+    assert_eq!(replace_all("ab", "X", "ababab"), "XXX");
+    assert_eq!(replace_all("aa", "b", "aaa"), "ba");
+    assert_eq!(
+        replace_all("café", "tea", "un café, deux cafés"),
+        "un tea, deux teas"
+    );
+    assert_eq!(replace_all("ab", "", "xabyab"), "xy");
+    assert_eq!(replace_all("moon", "sun", "no match here"), "no match here");
+    assert_eq!(replace_all("", "X", "abc"), "abc");
+}
+
+#[test]
+fn split_on_matches_str_split() {
+    assert_eq!(split_on(",", "a,b,,c"), vec!["a", "b", "", "c"]);
+
+    for (pattern, text) in [
+        (",", ",a,b,"),
+        (",", ""),
+        ("aa", "aaa"),
+        ("ab", "xabyabab"),
+        ("é", "café é"),
+        ("moon", "no match"),
+    ] {
+        assert_eq!(
+            split_on(pattern, text),
+            text.split(pattern).collect::<Vec<_>>(),
+            "{pattern:?} in {text:?}"
+        );
+    }
+
+    // unlike `str::split`, an empty pattern does not split at all
+    assert_eq!(split_on("", "abc"), vec!["abc"]);
+}
+
+#[test]
+fn byte_ranges_slice_matches() {
+    let text = "café ☕ and café ☕ then cafe";
+    let ranges = find_all_byte_ranges("café ☕", text);
+    assert_eq!(ranges, vec![(0, 9), (14, 23)]);
+    for (start, end) in ranges {
+        assert_eq!(&text[start..end], "café ☕");
+    }
+
+    assert_eq!(find_all_byte_ranges("ßß", "ßßßß"), vec![(0, 4), (4, 8)]);
+    assert_eq!(find_all_byte_ranges("é", "aéb"), vec![(1, 3)]);
+    assert_eq!(find_all_byte_ranges("", "aé"), vec![(0, 0), (1, 1), (3, 3)]);
+    assert!(find_all_byte_ranges("tea", text).is_empty());
+}
+
+#[test]
+fn longest_prefix_match_lengths() {
+    assert_eq!(longest_prefix_match("abcdef", "xxabcdyy"), 4);
+    assert_eq!(longest_prefix_match("abcdef", "xxabcdef"), 6);
+    assert_eq!(longest_prefix_match("aab", "abaaa"), 2);
+    assert_eq!(longest_prefix_match("abc", "xyz"), 0);
+    assert_eq!(longest_prefix_match("", "xyz"), 0);
+}
+
+#[test]
+fn borders_and_periods() {
+    assert_eq!(borders("aabaa"), vec![2, 1]);
+    assert_eq!(borders("abcabcab"), vec![5, 2]);
+    assert_eq!(borders("aaaa"), vec![3, 2, 1]);
+    assert!(borders("abc").is_empty());
+    assert!(borders("").is_empty());
+
+    assert_eq!(shortest_period("abcabcab"), 3);
+    assert_eq!(shortest_period("aabaa"), 3);
+    assert_eq!(shortest_period("aaaa"), 1);
+    assert_eq!(shortest_period("abc"), 3);
+    assert_eq!(shortest_period(""), 0);
+}
+
+#[test]
+fn multiline_confines_matches_to_lines() {
+    let text = "first line ends\nnext line starts";
+    assert!(contains_multiline("ends\nnext", text, true));
+    assert!(!contains_multiline("ends\nnext", text, false));
+    assert!(contains_multiline("s\nn", text, true));
+    assert!(!contains_multiline("s\nn", text, false));
+
+    assert!(contains_multiline("next line", text, false));
+    assert!(!contains_multiline("endsnext", text, true));
+    assert!(!contains_multiline("\n", text, false));
+}
+
+#[test]
+fn search_many_first_offsets() {
+    let texts = [
+        "Cats nap often, basking in warm spots.",
+        "Raindrops patter softly on windowpanes.",
+        "Stars twinkle brightly in the night.",
+        "Sunflowers turn eagerly towards the sun.",
+        "Snowflakes drift down gracefully from the sky.",
+    ];
+    assert_eq!(
+        search_many("the", &texts),
+        vec![None, None, Some(26), Some(32), Some(38)]
+    );
+    assert_eq!(search_many("zebra", &texts), vec![None; 5]);
+    assert_eq!(search_many("", &texts[..2]), vec![Some(0), Some(0)]);
+}
+
+#[test]
+fn search_many_mostly_negative_corpus() {
+    // most texts are rejected by the prefilter; some have every char of the
+    // pattern but not the pattern itself, and must still be searched
+    let mut texts = vec!["lorem ipsum dolor sit amet"; 1_000];
+    texts[10] = "the quick brown fox";
+    texts[500] = "quick, the fox said";
+    texts[999] = "xkcuiq";
+    let results = search_many("quick", &texts);
+
+    assert_eq!(results[10], Some(4));
+    assert_eq!(results[500], Some(0));
+    assert_eq!(results[999], None);
+    assert_eq!(results.iter().filter(|r| r.is_some()).count(), 2);
+
+    assert!(has_all_chars(&HashSet::from(['q', 'k']), "xkcuiq"));
+    assert!(!has_all_chars(&HashSet::from(['q', 'z']), "xkcuiq"));
+    assert!(has_all_chars(&HashSet::new(), ""));
+}
+
+#[test]
+fn contains_ordered_respects_order() {
+    let line = "2024-01-01 ERROR upstream timeout after 30s";
+    assert!(contains_ordered(&["ERROR", "timeout"], line));
+    assert!(!contains_ordered(&["timeout", "ERROR"], line));
+    assert!(contains_ordered(&["ERROR", "after", "30s"], line));
+    assert!(!contains_ordered(&["after", "upstream"], line));
+
+    // parts may not share chars
+    assert!(contains_ordered(&["ab", "ba"], "abba"));
+    assert!(!contains_ordered(&["aba", "ab"], "abab"));
+    assert!(contains_ordered(&["é", "é"], "éxé"));
+    assert!(contains_ordered(&[], "anything"));
+}
+
+#[test]
+fn find_in_reader_across_reads() {
+    use std::io::Cursor;
+
+    // the match starts two bytes before the end of the first read
+    let start = READ_BUFFER_SIZE - 2;
+    let mut text = "x".repeat(start);
+    text.push_str("néedle");
+    text.push_str(&"y".repeat(2000));
+
+    let found = find_in_reader("néedle", Cursor::new(text.as_bytes())).unwrap();
+    assert_eq!(found, Some(start as u64));
+    assert_eq!(&text[start..start + "néedle".len()], "néedle");
+
+    assert_eq!(
+        find_in_reader("xxy", Cursor::new(text.as_bytes())).unwrap(),
+        None
+    );
+    assert_eq!(find_in_reader("", Cursor::new(b"")).unwrap(), Some(0));
+    assert_eq!(find_in_reader("a", Cursor::new(b"")).unwrap(), None);
+}
+
+#[test]
+fn window_searcher_respects_window() {
+    let matches = |window_size: usize, text: &str| -> Vec<usize> {
+        let mut searcher = WindowSearcher::new("abcd", window_size);
+        let mut ends = Vec::new();
+        for (i, ch) in text.chars().enumerate() {
+            if searcher.push(ch) {
+                ends.push(i);
+            }
+        }
+        ends
+    };
+
+    assert_eq!(matches(4, "xxabcdabcd"), vec![5, 9]);
+    assert_eq!(matches(10, "xxabcdabcd"), vec![5, 9]);
+    // the start of each match has left a window of three chars
+    assert_eq!(matches(3, "xxabcdabcd"), Vec::<usize>::new());
+
+    let mut searcher = WindowSearcher::new("aa", 2);
+    let ends: Vec<bool> = "aaa".chars().map(|ch| searcher.push(ch)).collect();
+    assert_eq!(ends, vec![false, true, true]);
+
+    // the chars of a match must arrive less than five units apart
+    let timed = |pattern: &str, stream: &[(char, u64)]| -> Vec<u64> {
+        let mut searcher = WindowSearcher::new(pattern, 5);
+        stream
+            .iter()
+            .filter(|&&(ch, at)| searcher.push_at(ch, at))
+            .map(|&(_, at)| at)
+            .collect()
+    };
+    assert_eq!(timed("abc", &[('a', 0), ('b', 1), ('c', 4)]), vec![4]);
+    assert_eq!(timed("abc", &[('a', 10), ('b', 12), ('c', 15)]), vec![]);
+
+    // the first "ab" leaves the window, but the second completes in time
+    let stream = [('a', 0), ('b', 1), ('a', 4), ('b', 5), ('a', 6), ('b', 7)];
+    assert_eq!(timed("abab", &stream), vec![7]);
+}
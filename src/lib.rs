@@ -0,0 +1,153 @@
+//! A playground of substring search algorithms, along with the indexes and
+//! matchers built on top of them.
+
+pub mod aho_corasick;
+pub mod alternation;
+pub mod anchored;
+pub mod ascii;
+pub mod bio;
+pub mod boyer_moore;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod dfa;
+pub mod fuzzy;
+pub mod index;
+pub mod knuth_morris_pratt;
+pub mod naive;
+pub mod palindrome;
+pub mod pattern;
+pub mod profile;
+pub mod rabin_karp;
+pub mod searchable;
+pub mod suffix_array;
+pub mod token;
+pub mod trie;
+pub mod two_way;
+pub mod wildcard;
+pub mod z_algorithm;
+
+#[cfg(test)]
+mod test {
+    pub const TEST_PATTERN: &str = "abcde";
+
+    pub const TEST_CASES: [(&str, bool); 10] = [
+        ("abcdefghij", true),
+        ("12345abcde", true),
+        ("klabcdefgh", true),
+        ("qrabcdefst", true),
+        ("vwxyzabcde", true),
+        ("ijklmnopab", false),
+        ("fghijklmno", false),
+        ("pqrstuvwxyz", false),
+        ("lmnopqrst", false),
+        ("uvwxyzabcd", false),
+    ];
+
+    fn test_matcher(matcher: fn(&str, &str) -> bool) {
+        for (text, expected) in TEST_CASES {
+            let actual = matcher(TEST_PATTERN, text);
+            if actual != expected {
+                panic!(
+                    "expected {} for \"{text}\"",
+                    if expected { "match" } else { "no match" }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn naive() {
+        test_matcher(super::naive::contains);
+    }
+
+    #[test]
+    fn rabin_karp() {
+        test_matcher(super::rabin_karp::contains);
+    }
+
+    #[test]
+    fn boyer_moore() {
+        test_matcher(super::boyer_moore::contains);
+    }
+
+    #[test]
+    fn knuth_morris_pratt() {
+        test_matcher(super::knuth_morris_pratt::contains);
+    }
+
+    #[test]
+    fn two_way() {
+        test_matcher(super::two_way::contains);
+    }
+
+    #[test]
+    fn z_algorithm() {
+        test_matcher(super::z_algorithm::contains);
+    }
+
+    #[test]
+    fn wildcard() {
+        test_matcher(super::wildcard::contains_single_char);
+    }
+
+    #[test]
+    fn find_all_agrees() {
+        use super::*;
+
+        let cases: [(&str, &str, &[usize]); 7] = [
+            ("aa", "aaaa", &[0, 1, 2]),
+            ("aba", "abababa", &[0, 2, 4]),
+            ("abcde", "abcdeabcde", &[0, 5]),
+            ("é", "éaé", &[0, 2]),
+            ("xyz", "abcdef", &[]),
+            ("abc", "ab", &[]),
+            ("", "ab", &[0, 1, 2]),
+        ];
+
+        let finders: [fn(&str, &str) -> Vec<usize>; 4] = [
+            naive::find_all,
+            rabin_karp::find_all,
+            boyer_moore::find_all,
+            knuth_morris_pratt::find_all,
+        ];
+
+        for (pattern, text, expected) in cases {
+            for find_all in finders {
+                assert_eq!(find_all(pattern, text), expected, "{pattern:?} in {text:?}");
+            }
+        }
+    }
+
+    fn test_buffered_matcher(
+        matcher: fn(&str, &str) -> bool,
+        buffered: fn(&str, &str, &mut Vec<char>, &mut Vec<char>) -> bool,
+    ) {
+        let mut pattern_buf = Vec::new();
+        let mut text_buf = Vec::new();
+
+        // the first pass sizes the buffers for the longest text
+        for (text, _) in TEST_CASES {
+            buffered(TEST_PATTERN, text, &mut pattern_buf, &mut text_buf);
+        }
+
+        let capacities = (pattern_buf.capacity(), text_buf.capacity());
+        for (text, _) in TEST_CASES {
+            let actual = buffered(TEST_PATTERN, text, &mut pattern_buf, &mut text_buf);
+            assert_eq!(actual, matcher(TEST_PATTERN, text));
+            assert_eq!((pattern_buf.capacity(), text_buf.capacity()), capacities);
+        }
+    }
+
+    #[test]
+    fn buffers_are_reused() {
+        use super::*;
+
+        test_buffered_matcher(naive::contains, naive::contains_with_buffers);
+        test_buffered_matcher(rabin_karp::contains, rabin_karp::contains_with_buffers);
+        test_buffered_matcher(boyer_moore::contains, boyer_moore::contains_with_buffers);
+        test_buffered_matcher(
+            knuth_morris_pratt::contains,
+            knuth_morris_pratt::contains_with_buffers,
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use crate::profile::Counters;
+
+/// Naive string search checks for the presence of a match at each position
+/// of the input text. This requires no additional space but exhibits O(mn)
+/// time complexity in the worst case.
+pub fn contains(pattern: &str, text: &str) -> bool {
+    find(pattern, text).is_some()
+}
+
+/// Returns the char index (not the byte index) at which the first match
+/// starts. An empty pattern matches at 0.
+///
+/// The text is walked one char boundary at a time rather than collected, so
+/// only the pattern is copied and a match near the start of a large text is
+/// found without reading the rest of it.
+pub fn find(pattern: &str, text: &str) -> Option<usize> {
+    find_str_counted(pattern, text, &mut Counters::default())
+}
+
+fn find_str_counted(pattern: &str, text: &str, counters: &mut Counters) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    for (index, (i, _)) in text.char_indices().enumerate() {
+        if index > 0 {
+            counters.shifts += 1;
+        }
+
+        let mut window = text[i..].chars();
+        let matched = pattern.iter().all(|&p| match window.next() {
+            Some(ch) => {
+                counters.comparisons += 1;
+                ch == p
+            }
+            None => false,
+        });
+        if matched {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+/// Same as [`contains`], but ignores case, so `"ABC"` matches `"abcdef"`.
+/// Chars are compared by their lowercase forms, which covers ASCII, Latin-1
+/// and most other scripts. A char whose lowercase form is more than one char,
+/// such as `'İ'`, is compared as is. Folding is one char to one char, so
+/// matches that need a char to expand, such as `"STRASSE"` against
+/// `"straße"`, are not found.
+pub fn contains_ignore_case(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(fold_case).collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    text.char_indices().any(|(i, _)| {
+        let mut window = text[i..].chars().map(fold_case);
+        pattern.iter().all(|&p| window.next() == Some(p))
+    })
+}
+
+/// Maps a char to its lowercase form when that is a single char, and leaves
+/// it unchanged otherwise (e.g. `'İ'`, which lowercases to two chars).
+pub(crate) fn fold_case(ch: char) -> char {
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => ch,
+    }
+}
+
+/// The first match of a pattern along with the text around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Context {
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+/// Returns the first match together with up to `ctx` chars on either side of
+/// it, for showing as a search result snippet. The context is cut short where
+/// the match is near the start or end of the text.
+pub fn find_with_context(pattern: &str, text: &str, ctx: usize) -> Option<Context> {
+    let start = find(pattern, text)?;
+    let end = start + pattern.chars().count();
+
+    let chars: Vec<char> = text.chars().collect();
+    Some(Context {
+        before: chars[start.saturating_sub(ctx)..start].iter().collect(),
+        matched: chars[start..end].iter().collect(),
+        after: chars[end..chars.len().min(end + ctx)].iter().collect(),
+    })
+}
+
+/// Same as [`contains`], but searches a sequence of any comparable items,
+/// such as bytes or tokens, instead of the chars of a string.
+pub fn contains_slice<T: PartialEq>(pattern: &[T], text: &[T]) -> bool {
+    find_counted(pattern, text, &mut Counters::default()).is_some()
+}
+
+/// Same as [`contains`], but collects the pattern and text into the given
+/// buffers, which are cleared and refilled rather than reallocated. Reusing
+/// the buffers across many searches avoids allocating on every call.
+pub fn contains_with_buffers(
+    pattern: &str,
+    text: &str,
+    pattern_buf: &mut Vec<char>,
+    text_buf: &mut Vec<char>,
+) -> bool {
+    pattern_buf.clear();
+    pattern_buf.extend(pattern.chars());
+    text_buf.clear();
+    text_buf.extend(text.chars());
+    let (pattern, text) = (&pattern_buf[..], &text_buf[..]);
+
+    contains_counted(pattern, text, &mut Counters::default())
+}
+
+/// Runs the search over the collected items, recording the work done in
+/// `counters`.
+pub(crate) fn contains_counted<T: PartialEq>(
+    pattern: &[T],
+    text: &[T],
+    counters: &mut Counters,
+) -> bool {
+    find_counted(pattern, text, counters).is_some()
+}
+
+fn find_counted<T: PartialEq>(pattern: &[T], text: &[T], counters: &mut Counters) -> Option<usize> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    if text.is_empty() || text.len() < pattern.len() {
+        return None;
+    }
+
+    for i in 0..text.len() {
+        if i > 0 {
+            counters.shifts += 1;
+        }
+
+        if contains_inner(pattern, &text[i..], counters) {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
+/// Returns the char index of every match, including matches that overlap,
+/// so `"aa"` occurs at `[0, 1, 2]` in `"aaaa"`. An empty pattern matches at
+/// every char boundary.
+pub fn find_all(pattern: &str, text: &str) -> Vec<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if text.len() < pattern.len() {
+        return Vec::new();
+    }
+
+    (0..=text.len() - pattern.len())
+        .filter(|&i| contains_inner(&pattern, &text[i..], &mut Counters::default()))
+        .collect()
+}
+
+/// Aligns the pattern with the text starting at char `at` and returns the
+/// char index within the pattern where the two first differ, or `None` if
+/// the whole pattern matches there. Running off the end of the text counts
+/// as a difference. This explains why a search did not match at `at`.
+pub fn first_mismatch(pattern: &str, text: &str, at: usize) -> Option<usize> {
+    let mut text = text.chars().skip(at);
+    pattern.chars().position(|p| text.next() != Some(p))
+}
+
+/// Checks whether the reverse of the pattern occurs in the text, e.g.
+/// `"cba"` in `"xxabcyy"`. Rather than building a reversed copy of the
+/// pattern, each window of the text is compared against the pattern read
+/// back to front.
+pub fn contains_reversed(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    for window in text.windows(pattern.len()) {
+        if pattern.iter().rev().eq(window) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Same as [`contains`], but treats any run of whitespace (spaces, tabs,
+/// newlines) in either the pattern or the text as a single space, so
+/// `"the  sun"` matches `"the\tsun"`. The runs are collapsed on the fly
+/// while comparing rather than by building normalized copies.
+pub fn contains_collapsed_whitespace(pattern: &str, text: &str) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+
+    for (i, _) in text.char_indices() {
+        let mut text = collapse_whitespace(text[i..].chars());
+        if collapse_whitespace(pattern.chars()).all(|p| text.next() == Some(p)) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn collapse_whitespace(chars: impl Iterator<Item = char>) -> impl Iterator<Item = char> {
+    let mut in_whitespace = false;
+    chars.filter_map(move |ch| {
+        if !ch.is_whitespace() {
+            in_whitespace = false;
+            return Some(ch);
+        }
+
+        if in_whitespace {
+            return None;
+        }
+        in_whitespace = true;
+        Some(' ')
+    })
+}
+
+/// Splits the text into consecutive blocks of `window` chars (the last
+/// block may be shorter) and counts how many matches start within each
+/// block. Overlapping matches are all counted, as in [`find_all`], except
+/// that an empty pattern matching at the very end of the text is not within
+/// any block. Panics if `window` is zero.
+pub fn match_density(pattern: &str, text: &str, window: usize) -> Vec<usize> {
+    assert!(window > 0, "window must be non-zero");
+
+    let mut density = vec![0; text.chars().count().div_ceil(window)];
+    for i in find_all(pattern, text) {
+        if let Some(count) = density.get_mut(i / window) {
+            *count += 1;
+        }
+    }
+
+    density
+}
+
+fn contains_inner<T: PartialEq>(pattern: &[T], text: &[T], counters: &mut Counters) -> bool {
+    for (i, p) in pattern.iter().enumerate() {
+        if i == text.len() {
+            return false;
+        }
+
+        counters.comparisons += 1;
+        if &text[i] != p {
+            return false;
+        }
+    }
+    true
+}
+
+#[test]
+fn find_exits_early_in_large_text() {
+    let mut text = String::from("needle");
+    text.extend(std::iter::repeat_n('é', 1_000));
+
+    let mut counters = Counters::default();
+    assert_eq!(find_str_counted("needle", &text, &mut counters), Some(0));
+    assert_eq!(
+        counters,
+        Counters {
+            comparisons: 6,
+            shifts: 0
+        }
+    );
+    assert!(contains("needle", &text));
+    assert_eq!(find("néédle", "a néédle"), Some(2));
+    assert_eq!(find("éé", "é"), None);
+}
+
+#[test]
+fn contains_ignore_case_folds_both_sides() {
+    assert!(contains_ignore_case("ABC", "abcdef"));
+    assert!(contains_ignore_case("aBc", "xxAbCxx"));
+    assert!(contains_ignore_case("ÉTÉ", "un été chaud"));
+    assert!(contains_ignore_case("straße", "STRAßE"));
+    assert!(contains_ignore_case("ΣΟΦΙΑ", "σοφια"));
+    assert!(!contains_ignore_case("abd", "ABCDEF"));
+    // folding never expands a char
+    assert!(!contains_ignore_case("STRASSE", "straße"));
+}
+
+#[test]
+fn find_with_context_truncates_at_edges() {
+    let context = |before: &str, matched: &str, after: &str| Context {
+        before: before.to_string(),
+        matched: matched.to_string(),
+        after: after.to_string(),
+    };
+
+    let text = "the quick brown fox";
+    assert_eq!(
+        find_with_context("brown", text, 3),
+        Some(context("ck ", "brown", " fo"))
+    );
+    assert_eq!(
+        find_with_context("the", text, 3),
+        Some(context("", "the", " qu"))
+    );
+    assert_eq!(
+        find_with_context("fox", text, 3),
+        Some(context("wn ", "fox", ""))
+    );
+    assert_eq!(
+        find_with_context("quick", text, 100),
+        Some(context("the ", "quick", " brown fox"))
+    );
+    assert_eq!(
+        find_with_context("é", "café au lait", 2),
+        Some(context("af", "é", " a"))
+    );
+    assert_eq!(find_with_context("cow", text, 3), None);
+}
+
+#[test]
+fn contains_slice_compares_items() {
+    assert!(contains_slice(&["the", "cat"], &["see", "the", "cat"]));
+    assert!(!contains_slice(&["the", "cat"], &["the", "hat", "cat"]));
+    // a partial match at one start does not hide a match at the next
+    assert!(contains_slice(b"aab", b"aaab"));
+    assert!(contains_slice::<u8>(&[], b"abc"));
+    assert!(!contains_slice(&[1, 2, 3], &[1, 2]));
+}
+
+#[test]
+fn find_returns_char_index() {
+    assert_eq!(find("cd", "abcdcd"), Some(2));
+    assert_eq!(find("ô", "côté ô"), Some(1));
+    assert_eq!(find("té", "côté"), Some(2));
+    assert_eq!(find("", "abc"), Some(0));
+    assert_eq!(find("", ""), Some(0));
+    assert_eq!(find("abcd", "abc"), None);
+    assert_eq!(find("x", "abc"), None);
+}
+
+#[test]
+fn first_mismatch_positions() {
+    assert_eq!(first_mismatch("abc", "xxabcyy", 2), None);
+    assert_eq!(first_mismatch("abc", "xxabdyy", 2), Some(2));
+    assert_eq!(first_mismatch("abc", "xxabcyy", 3), Some(0));
+    assert_eq!(first_mismatch("abc", "xxab", 2), Some(2));
+    assert_eq!(first_mismatch("abc", "ab", 5), Some(0));
+    assert_eq!(first_mismatch("héllo", "héllø", 0), Some(4));
+    assert_eq!(first_mismatch("", "abc", 1), None);
+}
+
+#[test]
+fn contains_reversed_matches_mirror() {
+    assert!(contains_reversed("cba", "xxabcyy"));
+    assert!(!contains_reversed("abc", "xxabcyy"));
+    assert!(contains_reversed("aba", "xxabayy"));
+    assert!(!contains_reversed("cba", "ab"));
+}
+
+#[test]
+fn contains_collapsed_whitespace_ignores_runs() {
+    assert!(contains_collapsed_whitespace("the  sun", "under the sun"));
+    assert!(contains_collapsed_whitespace("the sun", "under the\tsun"));
+    assert!(contains_collapsed_whitespace(
+        "the sun",
+        "under the \n\t sun"
+    ));
+    assert!(contains_collapsed_whitespace("the\nsun", "under the   sun"));
+    assert!(!contains_collapsed_whitespace("the sun", "under thesun"));
+    assert!(!contains_collapsed_whitespace("thesun", "under the sun"));
+}
+
+#[test]
+fn match_density_counts_per_window() {
+    assert_eq!(match_density("ab", "ab__ab_ab___", 4), vec![1, 2, 0]);
+    assert_eq!(match_density("aa", "aaaaa", 2), vec![2, 2, 0]);
+    assert_eq!(match_density("ab", "", 4), Vec::<usize>::new());
+    assert_eq!(match_density("é", "éaé", 2), vec![1, 1]);
+    assert_eq!(match_density("", "abcd", 2), vec![2, 2]);
+}
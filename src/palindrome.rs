@@ -0,0 +1,89 @@
+use std::cmp::min;
+
+/// Returns the char range `start..end` of the longest palindromic substring
+/// of the text, preferring the leftmost one when there are several. An empty
+/// text yields `(0, 0)`.
+pub fn longest_palindrome(text: &str) -> (usize, usize) {
+    let mut longest = (0, 0);
+    for (center, len) in all_palindrome_centers(text).into_iter().enumerate() {
+        if len > longest.1 - longest.0 {
+            let start = (center - len) / 2;
+            longest = (start, start + len);
+        }
+    }
+    longest
+}
+
+/// Manacher's algorithm finds the longest palindrome around every center of
+/// the text in linear time. A palindrome has either a char or the gap between
+/// two chars at its center, so the text is conceptually interleaved with
+/// separators (`|a|b|a|`) and every position of the interleaved text is
+/// treated as a center. Entry `c` of the result is the length (in chars of
+/// the original text) of the longest palindrome centered at position `c`;
+/// even positions are gaps and odd positions are chars, for `2n + 1` entries
+/// in total.
+///
+/// The algorithm tracks the palindrome that reaches furthest right. A center
+/// inside it mirrors a center on the other side, whose radius is already
+/// known, so expansion can start from that radius (capped at the right edge)
+/// instead of from zero. The right edge only ever moves forward, which bounds
+/// the total work. The Wikipedia page for the algorithm has a useful
+/// explanation: https://en.wikipedia.org/wiki/Longest_palindromic_substring.
+pub fn all_palindrome_centers(text: &str) -> Vec<usize> {
+    // separators are None, which never equals a char
+    let mut interleaved = vec![None];
+    for ch in text.chars() {
+        interleaved.push(Some(ch));
+        interleaved.push(None);
+    }
+
+    let n = interleaved.len();
+    let mut radii = vec![0; n];
+    let mut center = 0;
+    let mut right = 0;
+    for i in 0..n {
+        if i < right {
+            radii[i] = min(right - i, radii[2 * center - i]);
+        }
+
+        while i > radii[i]
+            && i + radii[i] + 1 < n
+            && interleaved[i - radii[i] - 1] == interleaved[i + radii[i] + 1]
+        {
+            radii[i] += 1;
+        }
+
+        if i + radii[i] > right {
+            center = i;
+            right = i + radii[i];
+        }
+    }
+
+    // the radius in the interleaved text equals the length in the original
+    radii
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all_palindrome_centers, longest_palindrome};
+
+    fn longest(text: &str) -> String {
+        let (start, end) = longest_palindrome(text);
+        text.chars().skip(start).take(end - start).collect()
+    }
+
+    #[test]
+    fn longest_odd_and_even() {
+        assert_eq!(longest("babad"), "bab");
+        assert_eq!(longest("cbbd"), "bb");
+        assert_eq!(longest("forgeeksskeegfor"), "geeksskeeg");
+        assert_eq!(longest("a"), "a");
+        assert_eq!(longest_palindrome(""), (0, 0));
+    }
+
+    #[test]
+    fn centers_correct() {
+        assert_eq!(all_palindrome_centers("aba"), vec![0, 1, 0, 3, 0, 1, 0]);
+        assert_eq!(all_palindrome_centers("aa"), vec![0, 1, 2, 1, 0]);
+    }
+}
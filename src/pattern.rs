@@ -0,0 +1,243 @@
+use std::{ops::ControlFlow, str::CharIndices};
+
+use crate::knuth_morris_pratt;
+
+/// A pattern preprocessed once for Knuth-Morris-Pratt search, with methods
+/// that mirror the `str` methods taking a pattern. The standard library's
+/// `Pattern` trait is unstable, so this cannot plug into `str` directly.
+///
+/// Matches do not overlap: as with `str::matches`, the search resumes after
+/// the end of each match. Unlike `str`, an empty pattern never matches.
+pub struct CompiledPattern {
+    pattern: Vec<char>,
+    border_table: Vec<usize>,
+    byte_len: usize,
+}
+
+impl CompiledPattern {
+    pub fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        let border_table = knuth_morris_pratt::border_table(&chars);
+        Self {
+            pattern: chars,
+            border_table,
+            byte_len: pattern.len(),
+        }
+    }
+
+    /// Returns the matched slices of the text, like `str::matches`.
+    pub fn matches_in<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.match_ranges(text)
+            .map(|(start, end)| &text[start..end])
+    }
+
+    /// Returns the slices of the text between matches, like `str::split`.
+    /// Leading, trailing and adjacent matches produce empty slices.
+    pub fn split_on<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        Split {
+            ranges: self.match_ranges(text),
+            text,
+            start: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the first match, borrowing the matched slice from the text.
+    pub fn find_match<'a>(&self, text: &'a str) -> Option<Match<'a>> {
+        let (start, end) = self.match_ranges(text).next()?;
+        Some(Match {
+            start,
+            end,
+            text: &text[start..end],
+        })
+    }
+
+    fn match_ranges<'a>(&'a self, text: &'a str) -> MatchRanges<'a> {
+        MatchRanges {
+            pattern: self,
+            chars: text.char_indices(),
+            j: 0,
+        }
+    }
+}
+
+/// Calls `f` with each match in turn, as found by
+/// [`CompiledPattern::matches_in`], until it returns [`ControlFlow::Break`].
+/// The slices borrow from the text, so they can be kept after the search.
+pub fn for_each_match_slice<'a, F: FnMut(&'a str) -> ControlFlow<()>>(
+    pattern: &str,
+    text: &'a str,
+    mut f: F,
+) {
+    let compiled = CompiledPattern::new(pattern);
+    for (start, end) in compiled.match_ranges(text) {
+        if f(&text[start..end]).is_break() {
+            return;
+        }
+    }
+}
+
+/// A match found in some text, as the byte range it spans and the slice of
+/// the text at that range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a> {
+    pub start: usize,
+    pub end: usize,
+    text: &'a str,
+}
+
+impl<'a> Match<'a> {
+    /// Returns the matched slice, which lives as long as the searched text
+    /// rather than the match itself.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+}
+
+/// Yields the byte ranges of non-overlapping matches. The text is consumed
+/// one char at a time, since the KMP text cursor never moves backwards.
+struct MatchRanges<'a> {
+    pattern: &'a CompiledPattern,
+    chars: CharIndices<'a>,
+    j: usize,
+}
+
+impl Iterator for MatchRanges<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pattern = &self.pattern.pattern;
+        if pattern.is_empty() {
+            return None;
+        }
+
+        for (i, ch) in self.chars.by_ref() {
+            while self.j > 0 && ch != pattern[self.j] {
+                self.j = self.pattern.border_table[self.j - 1];
+            }
+
+            if ch == pattern[self.j] {
+                self.j += 1;
+            }
+
+            if self.j == pattern.len() {
+                self.j = 0;
+                let end = i + ch.len_utf8();
+                return Some((end - self.pattern.byte_len, end));
+            }
+        }
+
+        None
+    }
+}
+
+struct Split<'a> {
+    ranges: MatchRanges<'a>,
+    text: &'a str,
+    start: usize,
+    finished: bool,
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.ranges.next() {
+            Some((start, end)) => {
+                let piece = &self.text[self.start..start];
+                self.start = end;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(&self.text[self.start..])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use super::{for_each_match_slice, CompiledPattern};
+
+    const CASES: [(&str, &str); 6] = [
+        (",", "a,b,,c"),
+        (",", ",a,"),
+        ("ab", "xabyabab"),
+        ("aa", "aaaaa"),
+        ("é", "héllo wérld"),
+        ("xyz", "no match here"),
+    ];
+
+    #[test]
+    fn split_on_matches_str_split() {
+        for (pattern, text) in CASES {
+            let compiled = CompiledPattern::new(pattern);
+            let actual: Vec<&str> = compiled.split_on(text).collect();
+            let expected: Vec<&str> = text.split(pattern).collect();
+            assert_eq!(actual, expected, "{pattern:?} in {text:?}");
+        }
+    }
+
+    #[test]
+    fn matches_in_matches_str_matches() {
+        for (pattern, text) in CASES {
+            let compiled = CompiledPattern::new(pattern);
+            let actual: Vec<&str> = compiled.matches_in(text).collect();
+            let expected: Vec<&str> = text.matches(pattern).collect();
+            assert_eq!(actual, expected, "{pattern:?} in {text:?}");
+        }
+    }
+
+    #[test]
+    fn find_match_borrows_text() {
+        let text = String::from("héllo wérld");
+        let found = {
+            let compiled = CompiledPattern::new("wér");
+            compiled.find_match(&text)
+        };
+
+        let found = found.unwrap();
+        assert_eq!(found.as_str(), "wér");
+        assert_eq!((found.start, found.end), (7, 11));
+        assert_eq!(&text[found.start..found.end], found.as_str());
+        assert_eq!(CompiledPattern::new("xyz").find_match(&text), None);
+    }
+
+    #[test]
+    fn for_each_match_slice_stops_on_break() {
+        let text = String::from("one fish, two fish, red fish, blue fish");
+
+        let mut seen = Vec::new();
+        for_each_match_slice("fish", &text, |found| {
+            seen.push(found);
+            if seen.len() == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(seen, vec!["fish", "fish"]);
+
+        let mut count = 0;
+        for_each_match_slice("fish", &text, |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn empty_pattern_never_matches() {
+        let compiled = CompiledPattern::new("");
+        assert_eq!(compiled.find_match("abc"), None);
+        assert_eq!(compiled.matches_in("abc").count(), 0);
+        assert_eq!(compiled.split_on("abc").collect::<Vec<_>>(), vec!["abc"]);
+    }
+}
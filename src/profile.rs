@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use crate::{boyer_moore, knuth_morris_pratt, naive, rabin_karp};
+
+/// The search algorithms that can be profiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Naive,
+    RabinKarp,
+    BoyerMoore,
+    KnuthMorrisPratt,
+}
+
+/// Tallies of the basic operations performed during a search.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counters {
+    /// The number of times a text character was compared against a pattern
+    /// character.
+    pub comparisons: usize,
+    /// The number of times the pattern was moved along the text.
+    pub shifts: usize,
+}
+
+/// The outcome of a profiled search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchProfile {
+    pub algorithm: Algorithm,
+    pub found: bool,
+    pub comparisons: usize,
+    pub shifts: usize,
+    pub elapsed: Duration,
+}
+
+/// Runs a single search with the given algorithm and reports how much work it
+/// did. This makes it possible to compare the algorithms empirically on real
+/// data. The elapsed time covers the search itself (including any
+/// preprocessing of the pattern) but not the collection of the inputs into
+/// chars.
+pub fn profile(algorithm: Algorithm, pattern: &str, text: &str) -> SearchProfile {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let mut counters = Counters::default();
+    let start = Instant::now();
+    let found = match algorithm {
+        Algorithm::Naive => naive::contains_counted(&pattern, &text, &mut counters),
+        Algorithm::RabinKarp => rabin_karp::contains_counted(&pattern, &text, &mut counters),
+        Algorithm::BoyerMoore => boyer_moore::contains_counted(&pattern, &text, &mut counters),
+        Algorithm::KnuthMorrisPratt => {
+            knuth_morris_pratt::contains_counted(&pattern, &text, &mut counters)
+        }
+    };
+    let elapsed = start.elapsed();
+
+    SearchProfile {
+        algorithm,
+        found,
+        comparisons: counters.comparisons,
+        shifts: counters.shifts,
+        elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{profile, Algorithm};
+
+    const ALGORITHMS: [Algorithm; 4] = [
+        Algorithm::Naive,
+        Algorithm::RabinKarp,
+        Algorithm::BoyerMoore,
+        Algorithm::KnuthMorrisPratt,
+    ];
+
+    #[test]
+    fn profile_populated() {
+        for algorithm in ALGORITHMS {
+            let result = profile(algorithm, "abcde", "12345abcde");
+            assert_eq!(result.algorithm, algorithm);
+            assert!(result.found);
+            assert!(result.comparisons >= 5, "{algorithm:?}");
+            assert!(result.shifts > 0, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn profile_no_match() {
+        for algorithm in ALGORITHMS {
+            let result = profile(algorithm, "abcde", "fghijklmno");
+            assert!(!result.found);
+            assert!(result.shifts > 0, "{algorithm:?}");
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::profile::Counters;
+
+/// Rabin-Karp string search is similar to naive string search in that it
+/// checks for a match at every position of the input text. However, it
+/// skips the check at a given position if the hash of the substring at that
+/// position (of pattern length) does not match the hash of the pattern.
+///
+/// Computing a hash at a given position typically requires reading every
+/// character in the substring (and would be no better than naive search).
+/// Instead the algorithm makes use of a rolling hash, which allows the hash
+/// to be computed incrementally in constant time for each position. The
+/// following video provides a useful explanation of the rolling hash
+/// mechanism: https://www.youtube.com/watch?v=BfUejqd07yo. The following
+/// post is also useful for the same: https://stackoverflow.com/questions/6109624/
+/// need-help-in-understanding-rolling-hash-computation-in-constant-time-for-rabin-k.
+pub fn contains(pattern: &str, text: &str) -> bool {
+    contains_with_buffers(pattern, text, &mut Vec::new(), &mut Vec::new())
+}
+
+/// Same as [`contains`], but reuses the given buffers as described in
+/// [`naive::contains_with_buffers`](crate::naive::contains_with_buffers).
+pub fn contains_with_buffers(
+    pattern: &str,
+    text: &str,
+    pattern_buf: &mut Vec<char>,
+    text_buf: &mut Vec<char>,
+) -> bool {
+    pattern_buf.clear();
+    pattern_buf.extend(pattern.chars());
+    text_buf.clear();
+    text_buf.extend(text.chars());
+    let (pattern, text) = (&pattern_buf[..], &text_buf[..]);
+
+    contains_counted(pattern, text, &mut Counters::default())
+}
+
+/// Runs the search over the collected chars, recording the work done in
+/// `counters`.
+pub(crate) fn contains_counted(pattern: &[char], text: &[char], counters: &mut Counters) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+
+    if text.is_empty() || text.len() < pattern.len() {
+        return false;
+    }
+
+    let pattern_hash = RollingHasher::new(pattern).hash();
+    let mut text_hasher = RollingHasher::new(&text[..pattern.len()]);
+    for i in 0..text.len() {
+        if text[i..].len() < pattern.len() {
+            continue;
+        }
+
+        if i > 0 {
+            let in_ch = text[i + pattern.len() - 1];
+            let out_ch = text[i - 1];
+            text_hasher.roll(in_ch, out_ch);
+            counters.shifts += 1;
+        }
+
+        let text_hash = text_hasher.hash();
+        if text_hash != pattern_hash {
+            continue;
+        }
+
+        if contains_inner(pattern, &text[i..], counters) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Returns the char index of every match, including matches that overlap.
+/// The window hash keeps rolling past each match. An empty pattern matches
+/// at every char boundary.
+pub fn find_all(pattern: &str, text: &str) -> Vec<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if pattern.is_empty() {
+        return (0..=text.len()).collect();
+    }
+
+    if text.len() < pattern.len() {
+        return Vec::new();
+    }
+
+    let mut counters = Counters::default();
+    let pattern_hash = RollingHasher::new(&pattern).hash();
+    let mut text_hasher = RollingHasher::new(&text[..pattern.len()]);
+
+    let mut found = Vec::new();
+    for i in 0..=text.len() - pattern.len() {
+        if i > 0 {
+            text_hasher.roll(text[i + pattern.len() - 1], text[i - 1]);
+        }
+
+        if text_hasher.hash() == pattern_hash && contains_inner(&pattern, &text[i..], &mut counters)
+        {
+            found.push(i);
+        }
+    }
+    found
+}
+
+struct RollingHasher {
+    hash: u64,
+    /// `MULTIPLIER` raised to the window length minus one, which is the weight
+    /// of the char leaving the window.
+    leading_power: u64,
+}
+
+/// Exceeds every byte value, so that distinct ASCII windows map to distinct
+/// polynomials before reduction.
+const MULTIPLIER: u64 = 257;
+/// The Mersenne prime 2^61 - 1. A large prime modulus makes two different
+/// windows collide with probability around one in 2^61.
+const MODULO: u64 = (1 << 61) - 1;
+
+/// Multiplies modulo `MODULO`, widening so that the product cannot overflow.
+fn mul_mod(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % MODULO as u128) as u64
+}
+
+/// Raises `base` to `exp` modulo `MODULO` by repeated squaring, so the
+/// intermediate values never exceed the modulus.
+fn pow_mod(mut base: u64, mut exp: usize) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base);
+        }
+        base = mul_mod(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+impl RollingHasher {
+    fn new(init: &[char]) -> Self {
+        let mut hash = 0;
+        for ch in init {
+            hash = (mul_mod(hash, MULTIPLIER) + *ch as u64) % MODULO;
+        }
+
+        Self {
+            hash,
+            leading_power: pow_mod(MULTIPLIER, init.len().saturating_sub(1)),
+        }
+    }
+
+    fn roll(&mut self, in_ch: char, out_ch: char) {
+        let previous = mul_mod(out_ch as u64, self.leading_power);
+        self.hash = (self.hash + MODULO - previous) % MODULO;
+        self.hash = mul_mod(self.hash, MULTIPLIER);
+        self.hash = (self.hash + in_ch as u64) % MODULO;
+    }
+
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+#[test]
+fn rolled_hash_matches_direct_hash() {
+    let text: Vec<char> = "abc".chars().collect();
+    let mut hasher_a = RollingHasher::new(&text);
+    hasher_a.roll('a', 'a');
+
+    let text: Vec<char> = "bca".chars().collect();
+    let hasher_b = RollingHasher::new(&text);
+
+    assert_eq!(hasher_a.hash(), hasher_b.hash());
+
+    // long windows and chars outside ASCII do not overflow
+    let text: Vec<char> = "the quick brown fox jumps over the lazy dög"
+        .chars()
+        .collect();
+    let window = 30;
+    let mut rolling = RollingHasher::new(&text[..window]);
+    for i in 1..=text.len() - window {
+        rolling.roll(text[i + window - 1], text[i - 1]);
+        assert_eq!(
+            rolling.hash(),
+            RollingHasher::new(&text[i..i + window]).hash()
+        );
+    }
+}
+
+#[test]
+fn long_patterns() {
+    assert_eq!(pow_mod(MULTIPLIER, 0), 1);
+    assert_eq!(pow_mod(MULTIPLIER, 3), MULTIPLIER.pow(3));
+    assert_eq!(pow_mod(2, 61), 1);
+
+    let pattern: String = (0..1000)
+        .map(|i| char::from(b'a' + (i * 7 % 26) as u8))
+        .collect();
+    let text = format!("{}{pattern}{}", "x".repeat(500), "y".repeat(500));
+    assert!(contains(&pattern, &text));
+    assert_eq!(find_all(&pattern, &text), vec![500]);
+    assert!(!contains(&pattern, &text.replacen('a', "b", 1)));
+}
+
+#[test]
+fn few_hash_collisions() {
+    let text: Vec<char> = [
+        "Cats nap often, basking in warm spots.",
+        "Raindrops patter softly on windowpanes.",
+        "Stars twinkle brightly in the night.",
+        "Rivers flow quietly through lush valleys.",
+        "Birds chirp merrily at dawn's break.",
+    ]
+    .join(" ")
+    .repeat(20)
+    .chars()
+    .collect();
+
+    // every char compared belongs to the verification of the real match
+    let pattern: Vec<char> = "the".chars().collect();
+    let mut counters = Counters::default();
+    assert!(contains_counted(&pattern, &text, &mut counters));
+    assert_eq!(counters.comparisons, pattern.len());
+
+    let pattern: Vec<char> = "moonlight".chars().collect();
+    let mut counters = Counters::default();
+    assert!(!contains_counted(&pattern, &text, &mut counters));
+    assert_eq!(counters.comparisons, 0);
+}
+
+fn contains_inner(pattern: &[char], text: &[char], counters: &mut Counters) -> bool {
+    for (i, p) in pattern.iter().enumerate() {
+        if i == text.len() {
+            return false;
+        }
+
+        counters.comparisons += 1;
+        if &text[i] != p {
+            return false;
+        }
+    }
+    true
+}
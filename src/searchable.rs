@@ -0,0 +1,69 @@
+use crate::knuth_morris_pratt;
+
+/// A sequence that can be searched for a pattern of the same kind, giving one
+/// entry point for strings and slices alike. Strings are searched by char and
+/// slices by item, both with Knuth-Morris-Pratt.
+///
+/// Byte strings are covered by the slice implementation. Since `str` and
+/// slices have inherent `contains` methods of their own, which take priority,
+/// the trait method is called through the trait, as in
+/// `Searchable::contains(&text, pattern)`.
+pub trait Searchable {
+    /// Returns whether the pattern occurs in `self`. An empty pattern occurs
+    /// in anything.
+    fn contains(&self, pattern: Self) -> bool;
+}
+
+impl Searchable for &str {
+    fn contains(&self, pattern: Self) -> bool {
+        knuth_morris_pratt::contains(pattern, self)
+    }
+}
+
+impl<T: PartialEq> Searchable for &[T] {
+    fn contains(&self, pattern: Self) -> bool {
+        knuth_morris_pratt::contains_slice(pattern, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Searchable;
+
+    #[test]
+    fn strings() {
+        let text = "héllo wörld";
+        assert!(Searchable::contains(&text, "wö"));
+        assert!(Searchable::contains(&text, ""));
+        assert!(!Searchable::contains(&text, "world"));
+    }
+
+    #[test]
+    fn bytes() {
+        let text: &[u8] = b"GET /index.html 200";
+        assert!(Searchable::contains(&text, b"200".as_slice()));
+        assert!(!Searchable::contains(&text, b"404".as_slice()));
+    }
+
+    #[test]
+    fn slices() {
+        #[derive(PartialEq)]
+        enum Token {
+            Word(&'static str),
+            Number(u32),
+        }
+
+        let text: &[Token] = &[Token::Word("error"), Token::Number(500), Token::Word("at")];
+        assert!(Searchable::contains(
+            &text,
+            &[Token::Word("error"), Token::Number(500)][..]
+        ));
+        assert!(!Searchable::contains(
+            &text,
+            &[Token::Number(500), Token::Word("error")][..]
+        ));
+
+        let numbers: &[u32] = &[1, 2, 3, 4];
+        assert!(Searchable::contains(&numbers, &[2, 3][..]));
+    }
+}
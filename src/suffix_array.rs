@@ -0,0 +1,102 @@
+use std::ops::Range;
+
+/// A suffix array lists the start of every suffix of a text in sorted order,
+/// so the suffixes beginning with a given pattern form one contiguous range.
+/// The range is found by binary search, so a search costs O(m log n) char
+/// comparisons no matter how often the pattern occurs. The array is built
+/// once and pays off when many patterns are searched in the same text.
+///
+/// The suffixes are sorted by comparing them directly, which is simple but
+/// can take O(n² log n) time on highly repetitive texts.
+pub struct SuffixArray {
+    text: Vec<char>,
+    /// The char index of every suffix, including the empty one at the end,
+    /// in lexicographic order of the suffixes.
+    suffixes: Vec<usize>,
+}
+
+impl SuffixArray {
+    pub fn new(text: &str) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        let mut suffixes: Vec<usize> = (0..=text.len()).collect();
+        suffixes.sort_unstable_by(|&a, &b| text[a..].cmp(&text[b..]));
+        Self { text, suffixes }
+    }
+
+    /// Checks whether the pattern occurs in the text.
+    pub fn contains(&self, pattern: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        !self.range(&pattern).is_empty()
+    }
+
+    /// Returns the char index of every match in ascending order, including
+    /// matches that overlap. An empty pattern matches at every char boundary.
+    pub fn find_all(&self, pattern: &str) -> Vec<usize> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let mut found = self.suffixes[self.range(&pattern)].to_vec();
+        found.sort_unstable();
+        found
+    }
+
+    /// Returns the range of `suffixes` whose suffixes start with the pattern.
+    fn range(&self, pattern: &[char]) -> Range<usize> {
+        // only the first `pattern.len()` chars of each suffix are compared, so
+        // every suffix starting with the pattern compares equal to it
+        let prefix = |suffix: usize| {
+            let end = (suffix + pattern.len()).min(self.text.len());
+            &self.text[suffix..end]
+        };
+
+        let start = self.suffixes.partition_point(|&s| prefix(s) < pattern);
+        let end = self.suffixes.partition_point(|&s| prefix(s) <= pattern);
+        start..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SuffixArray;
+    use crate::naive;
+
+    #[test]
+    fn find_all_matches_naive() {
+        let text =
+            "she sells sea shells by the sea shore, and the shells she sells are surely seashells";
+        let array = SuffixArray::new(text);
+
+        assert_eq!(array.find_all("sea"), vec![10, 28, 75]);
+        assert_eq!(array.find_all("shore"), vec![32]);
+        assert!(array.find_all("ocean").is_empty());
+
+        let patterns = [
+            "she",
+            "sells",
+            "s",
+            "e",
+            "ll",
+            "sea",
+            "shells",
+            "ly",
+            "the sea",
+            "seashells",
+            "",
+            "zzz",
+            "surely seashells!",
+        ];
+        for pattern in patterns {
+            assert_eq!(
+                array.find_all(pattern),
+                naive::find_all(pattern, text),
+                "{pattern:?}"
+            );
+            assert_eq!(array.contains(pattern), naive::contains(pattern, text));
+        }
+
+        // overlapping matches and chars outside ASCII
+        let array = SuffixArray::new("aaaa");
+        assert_eq!(array.find_all("aa"), vec![0, 1, 2]);
+        let array = SuffixArray::new("café ☕ café");
+        assert_eq!(array.find_all("é"), vec![3, 10]);
+        assert_eq!(SuffixArray::new("").find_all(""), vec![0]);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::knuth_morris_pratt;
+
+/// Checks whether the pattern occurs as a run of consecutive tokens in the
+/// text, such as a sequence of words or log fields. Tokens are compared whole,
+/// so `["err"]` does not match `["error"]`. An empty pattern matches any text.
+pub fn contains(pattern: &[String], text: &[String]) -> bool {
+    knuth_morris_pratt::contains_slice(pattern, text)
+}
+
+/// Splits text into the words indexed by [`Index`](crate::index::Index) and
+/// [`Trie`](crate::trie::Trie). Words are separated by ASCII whitespace and
+/// ASCII punctuation, so `"often,"` yields `"often"` and `"dawn's"` yields
+/// `"dawn"` and `"s"`. Non-ASCII chars are always part of a word. Queries
+/// are split the same way, so a query written as in the text finds it.
+pub fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| ch.is_ascii_whitespace() || ch.is_ascii_punctuation())
+        .filter(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, words};
+
+    fn tokens(line: &str) -> Vec<String> {
+        line.split_ascii_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn matches_whole_tokens() {
+        let fields = tokens("2024-01-01 GET /index.html error 500 12ms");
+        assert!(contains(&tokens("error 500"), &fields));
+        assert!(!contains(&tokens("500 error"), &fields));
+        assert!(!contains(&tokens("err 500"), &fields));
+        assert!(!contains(&tokens("error 50"), &fields));
+        assert!(contains(&[], &fields));
+    }
+
+    #[test]
+    fn words_split_on_punctuation() {
+        let line = "Birds chirp merrily at dawn's break.";
+        assert_eq!(
+            words(line).collect::<Vec<_>>(),
+            vec!["Birds", "chirp", "merrily", "at", "dawn", "s", "break"]
+        );
+        assert_eq!(
+            words(" -- a,b  café! ").collect::<Vec<_>>(),
+            vec!["a", "b", "café"]
+        );
+    }
+}
@@ -1,56 +1,444 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, fmt, rc::Rc};
 
-struct Trie {
-    next: HashMap<char, Trie>,
-    occs: Vec<usize>,
+use crate::token;
+
+/// Children are reference counted and copied on write, so cloning a trie only
+/// copies the root. This makes [`Trie::snapshot`] cheap.
+#[derive(Clone)]
+pub struct Trie {
+    next: HashMap<char, Rc<Trie>>,
+    occs: Rc<Vec<usize>>,
+}
+
+/// Why [`Trie::from_bytes`] could not rebuild a trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes end partway through an entry.
+    UnexpectedEnd,
+    /// A stored word is not valid UTF-8.
+    InvalidUtf8,
+    /// The stored words are not in sorted order.
+    Unsorted,
+    /// Bytes are left over after the last entry.
+    TrailingBytes,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DecodeError::UnexpectedEnd => "unexpected end of input",
+            DecodeError::InvalidUtf8 => "word is not valid UTF-8",
+            DecodeError::Unsorted => "words are not sorted",
+            DecodeError::TrailingBytes => "trailing bytes after the last entry",
+        };
+        write!(f, "invalid trie encoding: {reason}")
+    }
+}
+
+impl Error for DecodeError {}
+
+/// A checkpoint of a [`Trie`] that it can later be restored to.
+pub struct TrieSnapshot {
+    root: Trie,
 }
 
 impl Trie {
-    fn new(corpus: &[&'static str]) -> Self {
+    pub fn new(corpus: &[&str]) -> Self {
         let mut root = Self::node();
 
         for (i, line) in corpus.iter().enumerate() {
-            line.split_ascii_whitespace().for_each(|word| {
-                let mut current = &mut root;
-                for char in word.chars() {
-                    if current.next.contains_key(&char) {
-                        current = current.next.get_mut(&char).unwrap();
-                    } else {
-                        current.next.insert(char, Self::node());
-                        current = current.next.get_mut(&char).unwrap();
-                    }
-                }
-                current.occs.push(i);
-            })
+            token::words(line).for_each(|word| root.insert(word, i))
+        }
+
+        root.share_occs(&mut HashMap::new());
+        root
+    }
+
+    /// Builds a trie from words that are already sorted, each with its
+    /// occurrences. Rather than descending from the root for every word, the
+    /// nodes on the path to the previous word are kept on a stack. Each word
+    /// only pops the nodes past its common prefix with the previous word,
+    /// which are complete since no later word can extend them, and pushes
+    /// nodes for its remaining chars. Panics in debug builds if the words are
+    /// not sorted.
+    pub fn from_sorted_words(words: &[(&str, Vec<usize>)]) -> Self {
+        debug_assert!(
+            words.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "words must be sorted"
+        );
+
+        let mut root = Self::node();
+        let mut path: Vec<(char, Trie)> = Vec::new();
+        let mut previous = "";
+
+        for (word, occs) in words {
+            let common = word
+                .chars()
+                .zip(previous.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            while path.len() > common {
+                root.pop_path(&mut path);
+            }
+
+            path.extend(word.chars().skip(common).map(|char| (char, Self::node())));
+            let node = match path.last_mut() {
+                Some((_, node)) => node,
+                None => &mut root,
+            };
+            Rc::make_mut(&mut node.occs).extend(occs);
+            previous = word;
+        }
+
+        while !path.is_empty() {
+            root.pop_path(&mut path);
         }
 
+        root.share_occs(&mut HashMap::new());
         root
     }
 
+    /// Moves the last node on `path` into its parent, which is the node before
+    /// it on the path or else `self`.
+    fn pop_path(&mut self, path: &mut Vec<(char, Trie)>) {
+        let (char, node) = path.pop().expect("path is not empty");
+        let parent = match path.last_mut() {
+            Some((_, parent)) => parent,
+            None => self,
+        };
+        parent.next.insert(char, Rc::new(node));
+    }
+
     fn node() -> Self {
         Self {
             next: HashMap::new(),
-            occs: Vec::new(),
+            occs: Rc::default(),
         }
     }
 
-    fn find(&self, word: &str) -> Option<Vec<usize>> {
+    /// Records an occurrence of `word` in document `doc`. Nodes shared with a
+    /// snapshot are copied before they are modified, so the snapshot is left
+    /// untouched.
+    pub fn insert(&mut self, word: &str, doc: usize) {
         let mut current = self;
         for char in word.chars() {
+            let child = current
+                .next
+                .entry(char)
+                .or_insert_with(|| Rc::new(Self::node()));
+            current = Rc::make_mut(child);
+        }
+        Rc::make_mut(&mut current.occs).push(doc);
+    }
+
+    /// Captures the current contents so they can be brought back with
+    /// [`Trie::restore`], e.g. to undo a bulk edit. Only the root is copied;
+    /// the rest of the structure is shared until either side modifies it.
+    pub fn snapshot(&self) -> TrieSnapshot {
+        TrieSnapshot { root: self.clone() }
+    }
+
+    /// Discards all changes made since `snapshot` was taken.
+    pub fn restore(&mut self, snapshot: TrieSnapshot) {
+        *self = snapshot.root;
+    }
+
+    /// Points every node whose occurrence list equals one already seen at that
+    /// earlier list, so that each distinct list is stored only once. Many words
+    /// appear in exactly the same documents, so this saves a lot of memory.
+    fn share_occs(&mut self, shared: &mut HashMap<Vec<usize>, Rc<Vec<usize>>>) {
+        match shared.get(self.occs.as_slice()) {
+            Some(occs) => self.occs = Rc::clone(occs),
+            None => {
+                shared.insert(self.occs.to_vec(), Rc::clone(&self.occs));
+            }
+        }
+
+        for child in self.next.values_mut() {
+            Rc::make_mut(child).share_occs(shared);
+        }
+    }
+
+    /// Shrinks the child map and occurrence list of every node to fit its
+    /// contents, reclaiming the spare capacity left over from building. Lists
+    /// that are shared between nodes are reallocated once and stay shared.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_inner(&mut HashMap::new());
+    }
+
+    fn shrink_inner(&mut self, fitted: &mut HashMap<Vec<usize>, Rc<Vec<usize>>>) {
+        self.next.shrink_to_fit();
+
+        match fitted.get(self.occs.as_slice()) {
+            Some(occs) => self.occs = Rc::clone(occs),
+            None => {
+                // cloning a vector allocates exactly its length
+                let occs = Rc::new(self.occs.to_vec());
+                fitted.insert(self.occs.to_vec(), Rc::clone(&occs));
+                self.occs = occs;
+            }
+        }
+
+        for child in self.next.values_mut() {
+            Rc::make_mut(child).shrink_inner(fitted);
+        }
+    }
+
+    /// Returns the occurrences of `word`. A prefix of a stored word that is
+    /// not itself a word has no occurrences. The query is split into words
+    /// like the documents, so `"often,"` finds `"often"`. The trie does not
+    /// record where words occur, so a query that splits into several words,
+    /// such as `"dawn's"`, finds each document containing all of them, once.
+    pub fn find(&self, word: &str) -> Option<Vec<usize>> {
+        let words: Vec<&str> = token::words(word).collect();
+        let (first, rest) = match words.split_first() {
+            Some(split) => split,
+            None => return Some(self.occs.to_vec()),
+        };
+        if rest.is_empty() {
+            return self.node_for(first).map(|node| node.occs.to_vec());
+        }
+
+        let mut found = self.node_for(first)?.occs.to_vec();
+        found.dedup();
+        for word in rest {
+            let occs = &self.node_for(word)?.occs;
+            found.retain(|doc| occs.contains(doc));
+        }
+        Some(found)
+    }
+
+    /// Returns the node reached by following the chars of `word`.
+    fn node_for(&self, word: &str) -> Option<&Trie> {
+        let mut current = self;
+        for char in word.chars() {
+            current = current.next.get(&char)?;
+        }
+        Some(current)
+    }
+
+    /// Suggests up to `k` words within `max_distance` edits (insertions,
+    /// deletions or substitutions) of `word`, closest first. Words at the same
+    /// distance are ranked by their number of occurrences, so the most common
+    /// correction comes first.
+    ///
+    /// Each node extends the edit distance row of its parent by one char, so
+    /// words sharing a prefix share that work, and branches whose whole row
+    /// exceeds `max_distance` are skipped since no word below them can get
+    /// closer.
+    pub fn correct(&self, word: &str, max_distance: usize, k: usize) -> Vec<String> {
+        let word: Vec<char> = word.chars().collect();
+        let row: Vec<usize> = (0..=word.len()).collect();
+
+        let mut found = Vec::new();
+        self.correct_inner(&word, &row, max_distance, &mut String::new(), &mut found);
+
+        found.sort_by(|(a, a_dist, a_count), (b, b_dist, b_count)| {
+            a_dist.cmp(b_dist).then(b_count.cmp(a_count)).then(a.cmp(b))
+        });
+        found.truncate(k);
+        found.into_iter().map(|(word, _, _)| word).collect()
+    }
+
+    fn correct_inner(
+        &self,
+        word: &[char],
+        row: &[usize],
+        max_distance: usize,
+        prefix: &mut String,
+        found: &mut Vec<(String, usize, usize)>,
+    ) {
+        let distance = row[word.len()];
+        if !self.occs.is_empty() && distance <= max_distance {
+            found.push((prefix.clone(), distance, self.occs.len()));
+        }
+
+        if row.iter().min().is_some_and(|&min| min > max_distance) {
+            return;
+        }
+
+        for (&char, child) in &self.next {
+            let mut next_row = vec![row[0] + 1];
+            for i in 1..=word.len() {
+                let cost = usize::from(word[i - 1] != char);
+                let best = (row[i - 1] + cost).min(row[i] + 1).min(next_row[i - 1] + 1);
+                next_row.push(best);
+            }
+
+            prefix.push(char);
+            child.correct_inner(word, &next_row, max_distance, prefix, found);
+            prefix.pop();
+        }
+    }
+
+    /// Returns every stored word with its occurrences, sorted by word. The
+    /// occurrence lists are borrowed rather than cloned.
+    pub fn entries(&self) -> impl Iterator<Item = (String, &[usize])> {
+        let mut entries = Vec::new();
+        self.entries_inner(&mut String::new(), &mut entries);
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries.into_iter()
+    }
+
+    fn entries_inner<'a>(&'a self, prefix: &mut String, entries: &mut Vec<(String, &'a [usize])>) {
+        if !self.occs.is_empty() {
+            entries.push((prefix.clone(), self.occs.as_slice()));
+        }
+
+        for (&char, child) in &self.next {
+            prefix.push(char);
+            child.entries_inner(prefix, entries);
+            prefix.pop();
+        }
+    }
+
+    /// Encodes the trie in a compact binary form that [`Trie::from_bytes`]
+    /// reads back. Only the [`Trie::entries`] are stored, in sorted order: the
+    /// number of entries, then for each the length of the word in bytes, the
+    /// word, the number of occurrences and the occurrences. Every number is a
+    /// little-endian `u64`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let entries: Vec<(String, &[usize])> = self.entries().collect();
+
+        let mut bytes = Vec::new();
+        bytes.extend((entries.len() as u64).to_le_bytes());
+        for (word, occs) in entries {
+            bytes.extend((word.len() as u64).to_le_bytes());
+            bytes.extend(word.as_bytes());
+            bytes.extend((occs.len() as u64).to_le_bytes());
+            for &doc in occs {
+                bytes.extend((doc as u64).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Rebuilds a trie encoded by [`Trie::to_bytes`]. The entries are stored
+    /// sorted, so the trie is rebuilt with [`Trie::from_sorted_words`].
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, DecodeError> {
+        let count = read_u64(&mut bytes)?;
+
+        let mut words: Vec<(&str, Vec<usize>)> = Vec::new();
+        for _ in 0..count {
+            let len = read_u64(&mut bytes)? as usize;
+            let word = read_bytes(&mut bytes, len)?;
+            let word = std::str::from_utf8(word).map_err(|_| DecodeError::InvalidUtf8)?;
+
+            let occ_count = read_u64(&mut bytes)?;
+            let occs = (0..occ_count)
+                .map(|_| read_u64(&mut bytes).map(|doc| doc as usize))
+                .collect::<Result<Vec<usize>, _>>()?;
+
+            if words.last().is_some_and(|&(previous, _)| previous > word) {
+                return Err(DecodeError::Unsorted);
+            }
+            words.push((word, occs));
+        }
+
+        if !bytes.is_empty() {
+            return Err(DecodeError::TrailingBytes);
+        }
+
+        Ok(Self::from_sorted_words(&words))
+    }
+
+    /// Returns the stored words that start with `prefix`, including the prefix
+    /// itself if it is a word, in sorted order. This is the list of
+    /// completions for autocomplete.
+    pub fn find_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut current = self;
+        for char in prefix.chars() {
             match current.next.get(&char) {
                 Some(node) => current = node,
-                None => return None,
+                None => return Vec::new(),
+            }
+        }
+
+        let mut entries = Vec::new();
+        current.entries_inner(&mut prefix.to_string(), &mut entries);
+
+        let mut words: Vec<String> = entries.into_iter().map(|(word, _)| word).collect();
+        words.sort_unstable();
+        words
+    }
+
+    /// Returns the stored words that start with `prefix`, ordering words that
+    /// end in a number by its value rather than by its digits, so `"item2"`
+    /// comes before `"item10"`. Words are compared by the text before their
+    /// trailing digits first, and words without a number come first.
+    pub fn find_prefix_numeric_sorted(&self, prefix: &str) -> Vec<String> {
+        let mut words = self.find_prefix(prefix);
+        words.sort_by_cached_key(|word| {
+            let stem = word.trim_end_matches(|ch: char| ch.is_ascii_digit());
+            let digits = word[stem.len()..].trim_start_matches('0');
+            // without leading zeros, a longer number is always larger
+            (
+                stem.to_string(),
+                digits.len(),
+                digits.to_string(),
+                word.clone(),
+            )
+        });
+        words
+    }
+
+    /// Removes a word and all of its occurrences, returning whether it was
+    /// present. Nodes left with no occurrences and no children are pruned,
+    /// while nodes still on the path to another word are kept.
+    pub fn delete(&mut self, word: &str) -> bool {
+        let word: Vec<char> = word.chars().collect();
+        self.delete_inner(&word)
+    }
+
+    fn delete_inner(&mut self, word: &[char]) -> bool {
+        let (first, rest) = match word.split_first() {
+            Some(split) => split,
+            None => {
+                if self.occs.is_empty() {
+                    return false;
+                }
+                self.occs = Rc::default();
+                return true;
             }
+        };
+
+        let child = match self.next.get_mut(first) {
+            Some(child) => Rc::make_mut(child),
+            None => return false,
+        };
+
+        let deleted = child.delete_inner(rest);
+        if deleted && child.next.is_empty() && child.occs.is_empty() {
+            self.next.remove(first);
         }
-        Some(current.occs.clone())
+        deleted
     }
 }
 
+/// Splits the next `len` bytes off the front of `bytes`.
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if bytes.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+/// Reads a little-endian `u64` off the front of `bytes`.
+fn read_u64(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    let head = read_bytes(bytes, 8)?;
+    Ok(u64::from_le_bytes(head.try_into().expect("read 8 bytes")))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Trie;
+    use std::{collections::HashSet, rc::Rc};
+
+    use super::{DecodeError, Trie};
+    use crate::token;
 
-    const CORPUS: [&'static str; 10] = [
+    const CORPUS: [&str; 10] = [
         "Cats nap often, basking in warm spots.",
         "Raindrops patter softly on windowpanes.",
         "Stars twinkle brightly in the night.",
@@ -76,4 +464,272 @@ mod tests {
         let in_occ = index.find("the");
         assert_eq!(in_occ, Some(vec![2, 8, 9]));
     }
+
+    fn distinct_occs(trie: &Trie, seen: &mut HashSet<*const Vec<usize>>) {
+        seen.insert(Rc::as_ptr(&trie.occs));
+        for child in trie.next.values() {
+            distinct_occs(child, seen);
+        }
+    }
+
+    #[test]
+    fn punctuated_queries_split_into_words() {
+        let trie = Trie::new(&CORPUS);
+
+        assert_eq!(trie.find("often,"), Some(vec![0]));
+        assert_eq!(trie.find("dawn's"), Some(vec![4]));
+
+        // punctuation leaves a prefix of a word with no occurrences of its own
+        assert_eq!(trie.find("bask,"), Some(vec![]));
+
+        // without positions, several words match wherever they all occur
+        assert_eq!(trie.find("night, the"), Some(vec![2]));
+        assert_eq!(trie.find("the-in"), Some(vec![2]));
+        assert_eq!(trie.find("the moon"), None);
+    }
+
+    #[test]
+    fn borrowed_runtime_corpus() {
+        let trie = {
+            let lines: Vec<String> = CORPUS.iter().map(|line| line.to_string()).collect();
+            let corpus: Vec<&str> = lines.iter().map(String::as_str).collect();
+            Trie::new(&corpus)
+        };
+
+        assert_eq!(trie.find("in"), Some(vec![0, 2, 7]));
+    }
+
+    #[test]
+    fn occurrence_lists_shared() {
+        let trie = Trie::new(&["one two three four", "five six seven", "one six"]);
+
+        // only [], [0], [1], [0, 2] and [1, 2] are stored
+        let mut seen = HashSet::new();
+        distinct_occs(&trie, &mut seen);
+        assert_eq!(seen.len(), 5);
+
+        assert_eq!(trie.find("three"), Some(vec![0]));
+        assert_eq!(trie.find("seven"), Some(vec![1]));
+        assert_eq!(trie.find("one"), Some(vec![0, 2]));
+        assert_eq!(trie.find("six"), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn delete_keeps_shared_path() {
+        let mut trie = Trie::new(&["car cart", "cart care"]);
+
+        assert!(trie.delete("car"));
+        assert_eq!(trie.find("car"), Some(vec![]));
+        assert_eq!(trie.find("cart"), Some(vec![0, 1]));
+        assert_eq!(trie.find("care"), Some(vec![1]));
+
+        assert!(!trie.delete("car"));
+        assert!(!trie.delete("ca"));
+        assert!(!trie.delete("cars"));
+
+        assert!(trie.delete("cart"));
+        assert!(trie.delete("care"));
+        assert_eq!(trie.find("c"), None);
+        assert!(trie.next.is_empty());
+    }
+
+    #[test]
+    fn correct_ranks_by_distance_then_frequency() {
+        let trie = Trie::new(&[
+            "the cat sat on the mat",
+            "the hat and the cat",
+            "a cart and a cat",
+            "then they ran",
+        ]);
+
+        // only "cat" is one edit away; "cart" needs two
+        assert_eq!(trie.correct("cbt", 1, 1), vec!["cat"]);
+        assert_eq!(trie.correct("cbt", 2, 2), vec!["cat", "cart"]);
+        assert_eq!(
+            trie.correct("cat", 1, 10),
+            vec!["cat", "cart", "hat", "mat", "sat"]
+        );
+        assert_eq!(trie.correct("thn", 1, 2), vec!["the", "then"]);
+        assert!(trie.correct("zzzz", 1, 5).is_empty());
+        assert!(trie.correct("cat", 1, 0).is_empty());
+
+        // at the same distance the more common word beats the earlier one
+        let trie = Trie::new(&["hat hat", "bat hat"]);
+        assert_eq!(trie.correct("cat", 1, 2), vec!["hat", "bat"]);
+        assert_eq!(trie.correct("cat", 1, 1), vec!["hat"]);
+    }
+
+    #[test]
+    fn entries_sorted_by_word() {
+        let trie = Trie::new(&["to be or", "not to be", "be"]);
+
+        let entries: Vec<(String, &[usize])> = trie.entries().collect();
+        let expected: Vec<(String, &[usize])> = vec![
+            ("be".to_string(), &[0, 1, 2]),
+            ("not".to_string(), &[1]),
+            ("or".to_string(), &[0]),
+            ("to".to_string(), &[0, 1]),
+        ];
+        assert_eq!(entries, expected);
+
+        assert_eq!(Trie::new(&[]).entries().count(), 0);
+    }
+
+    #[test]
+    fn find_prefix_completes_words() {
+        let trie = Trie::new(&CORPUS);
+
+        assert_eq!(trie.find_prefix("Sn"), vec!["Snowflakes"]);
+        assert_eq!(
+            trie.find_prefix("S"),
+            vec!["Snowflakes", "Stars", "Sunflowers"]
+        );
+        assert_eq!(trie.find_prefix("th"), vec!["the", "through"]);
+        assert_eq!(trie.find_prefix("the"), vec!["the"]);
+        assert!(trie.find_prefix("zebra").is_empty());
+        assert_eq!(trie.find_prefix("").len(), trie.entries().count());
+    }
+
+    #[test]
+    fn find_prefix_numeric_sorted_orders_by_value() {
+        let trie = Trie::new(&["item10 item2 item1", "item item007 item20 itemA", "other3"]);
+
+        assert_eq!(
+            trie.find_prefix_numeric_sorted("item"),
+            vec!["item", "item1", "item2", "item007", "item10", "item20", "itemA"]
+        );
+        assert_eq!(
+            trie.find_prefix_numeric_sorted("item1"),
+            vec!["item1", "item10"]
+        );
+        assert!(trie.find_prefix_numeric_sorted("thing").is_empty());
+    }
+
+    #[test]
+    fn from_sorted_words_matches_inserts() {
+        let inserted = Trie::new(&CORPUS);
+        let words: Vec<(String, Vec<usize>)> = inserted
+            .entries()
+            .map(|(word, occs)| (word, occs.to_vec()))
+            .collect();
+        let words: Vec<(&str, Vec<usize>)> = words
+            .iter()
+            .map(|(word, occs)| (word.as_str(), occs.clone()))
+            .collect();
+
+        let built = Trie::from_sorted_words(&words);
+        for (word, _) in &words {
+            assert_eq!(built.find(word), inserted.find(word), "{word}");
+        }
+        for prefix in ["S", "Sn", "th", "zebra"] {
+            assert_eq!(built.find(prefix), inserted.find(prefix), "{prefix}");
+        }
+        assert!(built.entries().eq(inserted.entries()));
+
+        let built =
+            Trie::from_sorted_words(&[("car", vec![0]), ("car", vec![2]), ("cart", vec![1])]);
+        assert_eq!(built.find("car"), Some(vec![0, 2]));
+        assert_eq!(built.find("cart"), Some(vec![1]));
+    }
+
+    fn capacity(trie: &Trie) -> usize {
+        let own = trie.next.capacity() + trie.occs.capacity();
+        own + trie
+            .next
+            .values()
+            .map(|child| capacity(child))
+            .sum::<usize>()
+    }
+
+    #[test]
+    fn shrink_to_fit_reclaims_capacity() {
+        let mut trie = Trie::new(&CORPUS);
+
+        let before = capacity(&trie);
+        trie.shrink_to_fit();
+        assert!(capacity(&trie) < before);
+
+        assert_eq!(trie.find("in"), Some(vec![0, 2, 7]));
+        assert_eq!(trie.find("the"), Some(vec![2, 8, 9]));
+
+        // shared lists stay shared
+        let mut shrunk = HashSet::new();
+        distinct_occs(&trie, &mut shrunk);
+        let mut built = HashSet::new();
+        distinct_occs(&Trie::new(&CORPUS), &mut built);
+        assert_eq!(shrunk.len(), built.len());
+    }
+
+    #[test]
+    fn restore_undoes_inserts() {
+        let mut trie = Trie::new(&["one two", "two three"]);
+        trie.insert("four", 2);
+
+        let snapshot = trie.snapshot();
+        trie.insert("five", 3);
+        trie.insert("two", 3);
+        trie.delete("one");
+        assert_eq!(trie.find("five"), Some(vec![3]));
+        assert_eq!(trie.find("two"), Some(vec![0, 1, 3]));
+
+        trie.restore(snapshot);
+        assert_eq!(trie.find("five"), None);
+        assert_eq!(trie.find("two"), Some(vec![0, 1]));
+        assert_eq!(trie.find("one"), Some(vec![0]));
+        assert_eq!(trie.find("four"), Some(vec![2]));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let trie = Trie::new(&CORPUS);
+        let bytes = trie.to_bytes();
+        let decoded = Trie::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.find("the"), Some(vec![2, 8, 9]));
+        assert!(decoded.entries().eq(trie.entries()));
+        for line in CORPUS {
+            for word in token::words(line) {
+                assert_eq!(decoded.find(word), trie.find(word), "{word}");
+            }
+        }
+        assert_eq!(decoded.find("moon"), None);
+
+        let empty = Trie::new(&[]);
+        assert_eq!(
+            Trie::from_bytes(&empty.to_bytes())
+                .unwrap()
+                .entries()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn invalid_bytes_rejected() {
+        let bytes = Trie::new(&CORPUS).to_bytes();
+
+        assert_eq!(
+            Trie::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Trie::from_bytes(&[bytes.as_slice(), &[0]].concat()).err(),
+            Some(DecodeError::TrailingBytes)
+        );
+
+        let mut unsorted = Trie::from_sorted_words(&[("b", vec![0])]).to_bytes();
+        unsorted[0] = 2;
+        unsorted.extend(Trie::from_sorted_words(&[("a", vec![1])]).to_bytes()[8..].to_vec());
+        assert_eq!(
+            Trie::from_bytes(&unsorted).err(),
+            Some(DecodeError::Unsorted)
+        );
+
+        let mut invalid = Trie::from_sorted_words(&[("a", vec![0])]).to_bytes();
+        invalid[16] = 0xff;
+        assert_eq!(
+            Trie::from_bytes(&invalid).err(),
+            Some(DecodeError::InvalidUtf8)
+        );
+    }
 }
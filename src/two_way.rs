@@ -0,0 +1,170 @@
+/// The Two-Way algorithm of Crochemore and Perrin, which is behind many
+/// standard library `memmem` implementations, searches in linear time using
+/// only a constant amount of extra space.
+///
+/// The pattern is split at a critical factorization into a left part `u` and
+/// a right part `v`, chosen so that the local period at the split equals the
+/// period of the whole pattern. At each alignment, `v` is compared left to
+/// right and then `u` right to left. A mismatch in `v` shifts the pattern past
+/// the mismatched char, while a mismatch in `u` (after all of `v` matched)
+/// shifts it by the period. When the pattern is periodic, the prefix already
+/// known to match after such a shift is remembered and not compared again.
+///
+/// The search runs over the UTF-8 bytes of both strings, where a substring
+/// match is exactly a match of the encoded bytes, so nothing is copied. It
+/// makes at most 2n comparisons on a text of n bytes, whatever the input.
+/// Boyer-Moore, by contrast, can be driven to O(mn) comparisons by
+/// adversarial inputs such as a highly repetitive pattern and text.
+pub fn contains(pattern: &str, text: &str) -> bool {
+    find(pattern.as_bytes(), text.as_bytes()).is_some()
+}
+
+/// Returns the index of the first match.
+fn find<T: Ord>(pattern: &[T], text: &[T]) -> Option<usize> {
+    let m = pattern.len();
+    let n = text.len();
+
+    if m == 0 {
+        return Some(0);
+    }
+
+    if n < m {
+        return None;
+    }
+
+    // `ell` is the last index of the left part, or -1 if it is empty
+    let (ell, period) = critical_factorization(pattern);
+    let left = (ell + 1) as usize;
+
+    let periodic = pattern[..left] == pattern[period..period + left];
+    if periodic {
+        // the number of chars at the start of the window already known to match
+        let mut memory = 0;
+        let mut j = 0;
+        while j <= n - m {
+            let mut i = left.max(memory);
+            while i < m && pattern[i] == text[i + j] {
+                i += 1;
+            }
+
+            if i < m {
+                j += i + 1 - left;
+                memory = 0;
+                continue;
+            }
+
+            let mut i = left;
+            while i > memory && pattern[i - 1] == text[i - 1 + j] {
+                i -= 1;
+            }
+
+            if i <= memory {
+                return Some(j);
+            }
+            j += period;
+            memory = m - period;
+        }
+    } else {
+        let shift = left.max(m - left) + 1;
+        let mut j = 0;
+        while j <= n - m {
+            let mut i = left;
+            while i < m && pattern[i] == text[i + j] {
+                i += 1;
+            }
+
+            if i < m {
+                j += i + 1 - left;
+                continue;
+            }
+
+            let mut i = left;
+            while i > 0 && pattern[i - 1] == text[i - 1 + j] {
+                i -= 1;
+            }
+
+            if i == 0 {
+                return Some(j);
+            }
+            j += shift;
+        }
+    }
+
+    None
+}
+
+/// Returns a critical factorization of the pattern as the last index of the
+/// left part (-1 if it is empty) and the period of the right part. It is the
+/// later of the starts of the maximal suffixes for the two orderings of the
+/// alphabet.
+fn critical_factorization<T: Ord>(pattern: &[T]) -> (isize, usize) {
+    let (ms_less, period_less) = maximal_suffix(pattern, false);
+    let (ms_greater, period_greater) = maximal_suffix(pattern, true);
+
+    if ms_less > ms_greater {
+        (ms_less, period_less)
+    } else {
+        (ms_greater, period_greater)
+    }
+}
+
+/// Returns the start of the lexicographically maximal suffix minus one and
+/// the period of that suffix. With `reversed`, the order of the alphabet is
+/// reversed.
+fn maximal_suffix<T: Ord>(pattern: &[T], reversed: bool) -> (isize, usize) {
+    let n = pattern.len() as isize;
+
+    let mut ms: isize = -1;
+    let mut j: isize = 0;
+    let mut k: isize = 1;
+    let mut period: isize = 1;
+    while j + k < n {
+        let a = &pattern[(j + k) as usize];
+        let b = &pattern[(ms + k) as usize];
+
+        let ahead = if reversed { a > b } else { a < b };
+        if ahead {
+            j += k;
+            k = 1;
+            period = j - ms;
+        } else if a == b {
+            if k != period {
+                k += 1;
+            } else {
+                j += period;
+                k = 1;
+            }
+        } else {
+            ms = j;
+            j = ms + 1;
+            k = 1;
+            period = 1;
+        }
+    }
+
+    (ms, period as usize)
+}
+
+#[test]
+fn critical_factorization_splits_pattern() {
+    let pattern: Vec<char> = "abaabaa".chars().collect();
+    assert_eq!(critical_factorization(&pattern), (1, 3));
+
+    let pattern: Vec<char> = "aaaa".chars().collect();
+    assert_eq!(critical_factorization(&pattern), (-1, 1));
+}
+
+#[test]
+fn finds_first_match() {
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    assert_eq!(find(&chars("abaabaa"), &chars("abaabaabaabaa")), Some(0));
+    assert_eq!(find(&chars("aab"), &chars("aaaaab")), Some(3));
+    assert_eq!(find(&chars("abc"), &chars("ababcab")), Some(2));
+    assert_eq!(find(&chars("banana"), &chars("bananas")), Some(0));
+    assert_eq!(find(&chars("xyz"), &chars("xyxyxy")), None);
+    assert!(contains("é", "café"));
+    assert!(contains("fé", "café"));
+    // 'é' and 'è' share their first byte
+    assert!(!contains("è", "café"));
+    assert!(!contains("aè", "café"));
+}
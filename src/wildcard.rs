@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+/// Checks whether the pattern occurs in the text, where each `?` in the
+/// pattern matches any single char. There is no escape for a literal `?`.
+///
+/// Uses the bitap (shift-and) algorithm. Bit `i` of the state is set when the
+/// first `i + 1` pattern chars match the text ending at the current char, so
+/// each text char updates every partial match at once with a shift and a
+/// mask. A `?` is set in the mask of every char, which keeps the search
+/// linear without any backtracking. Patterns longer than the 64 bits of the
+/// state fall back to comparing each window directly.
+pub fn contains_single_char(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if pattern.is_empty() {
+        return true;
+    }
+
+    if pattern.len() > u64::BITS as usize {
+        let text: Vec<char> = text.chars().collect();
+        return text.windows(pattern.len()).any(|window| {
+            window
+                .iter()
+                .zip(&pattern)
+                .all(|(&ch, &p)| p == '?' || p == ch)
+        });
+    }
+
+    let mut masks: HashMap<char, u64> = HashMap::new();
+    let mut wildcards = 0;
+    for (i, &ch) in pattern.iter().enumerate() {
+        if ch == '?' {
+            wildcards |= 1 << i;
+        } else {
+            *masks.entry(ch).or_default() |= 1 << i;
+        }
+    }
+
+    let found = 1 << (pattern.len() - 1);
+    let mut state: u64 = 0;
+    for ch in text.chars() {
+        let mask = masks.get(&ch).copied().unwrap_or(0) | wildcards;
+        state = ((state << 1) | 1) & mask;
+        if state & found != 0 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Checks whether the whole text matches a glob-style pattern, where `?`
+/// matches any single char and `*` matches any run of chars, including an
+/// empty one. Unlike [`contains_single_char`], the match is anchored at both
+/// ends, so an empty pattern matches only an empty text and `"*"` matches
+/// any text. There is no escape for a literal `?` or `*`.
+///
+/// Matches greedily and backtracks only to the most recent `*`, letting it
+/// absorb one more text char each time the rest of the pattern fails. An
+/// earlier `*` never needs to be revisited, since whatever the later one
+/// matches could have been matched by the earlier one too. Consecutive `*`s
+/// therefore behave as one, and the worst case is O(mn).
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut i, mut j) = (0, 0);
+    // the pattern position after the last `*` and the text position its run ends at
+    let mut star: Option<(usize, usize)> = None;
+    while i < text.len() {
+        if j < pattern.len() && pattern[j] == '*' {
+            j += 1;
+            star = Some((j, i));
+        } else if j < pattern.len() && (pattern[j] == '?' || pattern[j] == text[i]) {
+            i += 1;
+            j += 1;
+        } else if let Some((after_star, matched)) = star {
+            j = after_star;
+            i = matched + 1;
+            star = Some((after_star, i));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[j..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains_single_char, matches};
+    use crate::test::TEST_CASES;
+
+    #[test]
+    fn question_mark_matches_any_char() {
+        assert!(contains_single_char("a?c", "abc"));
+        assert!(contains_single_char("a?c", "axc"));
+        assert!(contains_single_char("a?c", "xxa?cxx"));
+        assert!(contains_single_char("a?c", "aébc aéc"));
+        assert!(!contains_single_char("a?c", "ac"));
+        assert!(!contains_single_char("a?c", "abbc"));
+        assert!(contains_single_char("???", "abc"));
+        assert!(!contains_single_char("???", "ab"));
+        assert!(contains_single_char("", ""));
+    }
+
+    #[test]
+    fn test_cases_with_wildcards() {
+        for pattern in ["abcde", "ab?de", "?bcd?", "a???e"] {
+            for (text, expected) in TEST_CASES {
+                assert_eq!(
+                    contains_single_char(pattern, text),
+                    expected,
+                    "{pattern} in {text}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn long_patterns() {
+        let text = "x".repeat(100) + &"ab".repeat(40);
+        let pattern = "a?".repeat(40);
+        assert!(contains_single_char(&pattern, &text));
+        assert!(!contains_single_char(&("b?".repeat(40) + "b"), &text));
+    }
+
+    #[test]
+    fn glob_matches_whole_text() {
+        assert!(matches("a*e", "abcde"));
+        assert!(matches("a*e", "ae"));
+        assert!(matches("a*e", "aeae"));
+        assert!(!matches("a*e", "abcdef"));
+        assert!(!matches("a*e", "xabcde"));
+
+        assert!(matches("a?cde", "abcde"));
+        assert!(matches("a?cde", "aécde"));
+        assert!(!matches("a?cde", "acde"));
+        assert!(!matches("a?cde", "abcdex"));
+
+        assert!(matches("*", ""));
+        assert!(matches("*", "anything at all"));
+        assert!(matches("a**b***", "axxb"));
+        assert!(matches("*a*b", "xaybzab"));
+        assert!(!matches("*a*b", "xaybzba"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn glob_star_against_literal_wildcards_in_text() {
+        assert!(matches("*", "*x"));
+        assert!(matches("*b", "*ab"));
+        assert!(matches("a*", "a*x"));
+        assert!(matches("*", "?"));
+        assert!(matches("?*", "?*?"));
+        assert!(matches("a*c", "a*?c"));
+        assert!(!matches("a*c", "a*?"));
+    }
+}
@@ -0,0 +1,70 @@
+/// The Z-algorithm computes, for every position of a string, the length of
+/// the longest substring starting there that is also a prefix of the string
+/// (the Z-array). Searching for a pattern amounts to computing the Z-array of
+/// the pattern, a separator and the text: a Z-value equal to the pattern
+/// length marks a match.
+///
+/// The separator must not occur in either string, or a match could run past
+/// the pattern into the text. Rather than reserving a char for it, the chars
+/// are wrapped in `Some` and the separator is `None`. The array is computed
+/// in linear time by reusing the rightmost window known to match a prefix,
+/// so the whole search takes O(m + n).
+pub fn contains(pattern: &str, text: &str) -> bool {
+    let pattern_len = pattern.chars().count();
+    if pattern_len == 0 {
+        return true;
+    }
+
+    let combined: Vec<Option<char>> = pattern
+        .chars()
+        .map(Some)
+        .chain([None])
+        .chain(text.chars().map(Some))
+        .collect();
+
+    z_array(&combined)
+        .into_iter()
+        .skip(pattern_len + 1)
+        .any(|z| z == pattern_len)
+}
+
+/// Returns the Z-array of `s`. The first entry is defined as zero.
+fn z_array<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let mut z = vec![0; s.len()];
+
+    // s[left..right] is the rightmost window found to match a prefix of s
+    let (mut left, mut right) = (0, 0);
+    for i in 1..s.len() {
+        if i < right {
+            z[i] = z[i - left].min(right - i);
+        }
+
+        while i + z[i] < s.len() && s[z[i]] == s[i + z[i]] {
+            z[i] += 1;
+        }
+
+        if i + z[i] > right {
+            left = i;
+            right = i + z[i];
+        }
+    }
+
+    z
+}
+
+#[test]
+fn z_array_correct() {
+    let s: Vec<char> = "aabxaab".chars().collect();
+    assert_eq!(z_array(&s), vec![0, 1, 0, 0, 3, 1, 0]);
+
+    let s: Vec<char> = "aaaaa".chars().collect();
+    assert_eq!(z_array(&s), vec![0, 4, 3, 2, 1]);
+}
+
+#[test]
+fn separator_stops_matches() {
+    assert!(contains("aa", "xaa"));
+    assert!(!contains("aa", "a"));
+    assert!(contains("é", "café"));
+    assert!(!contains("abc", ""));
+}